@@ -0,0 +1,77 @@
+//! Regenerates the rows of the `sources!` table in `src/handler/constants.rs` from a live search.
+//!
+//! Searching every index in testmode makes SauceNAO return one result per index, named like
+//! `Index #5: Pixiv Images - example.jpg`, along with the status of every index it has in the
+//! header.  Known indices are printed as their current row, and unknown ones as a comment with the
+//! name SauceNAO gave them, to be given a variant, slug, category and rating by hand.
+//!
+//! Run with `SAUCENAO_API_KEY=... cargo run --example source_table`.
+
+use rustnao::{HandlerBuilder, SearchOptions, Source};
+use std::collections::BTreeMap;
+
+#[tokio::main]
+async fn main() {
+    let api_key = std::env::var("SAUCENAO_API_KEY").unwrap_or_else(|_| "your_api_key".into());
+    let file = "https://i.imgur.com/W42kkKS.jpg";
+
+    let handle = HandlerBuilder::default().api_key(&api_key).build();
+    let mut options = SearchOptions::default();
+    options
+        .db(999)
+        .testmode(true)
+        .num_results(999)
+        .min_similarity(0)
+        .empty_filter_enabled(false);
+
+    let response = match handle.search(file, &options).await {
+        Ok(response) => response,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            return;
+        }
+    };
+
+    // The name of each index, from the index_name of its result, such as "Index #5: Pixiv Images - example.jpg".
+    let mut names: BTreeMap<u32, Option<String>> = response
+        .meta
+        .indexes
+        .keys()
+        .map(|index| (*index, None))
+        .collect();
+    for sauce in &response.results {
+        let name = sauce
+            .site
+            .split_once(": ")
+            .map(|(_, rest)| rest.split(" - ").next().unwrap_or(rest).to_string());
+        names.insert(sauce.index_id, name);
+    }
+
+    for (index, name) in names {
+        let parent = response
+            .meta
+            .indexes
+            .get(&index)
+            .map(|x| x.parent_id)
+            .filter(|parent| *parent != index);
+        match (Source::from(index), parent) {
+            (Source::Unknown(_), Some(parent)) => {
+                println!("    // Index {}: part of index {}", index, parent)
+            }
+            (Source::Unknown(_), None) => println!(
+                "    // Index {}: {}",
+                index,
+                name.as_deref().unwrap_or("not named by SauceNAO")
+            ),
+            (source, _) => println!(
+                "    {:?} = {}, {:?}, {:?}, {:?}, {};",
+                source,
+                index,
+                source.slug(),
+                source.name(),
+                source.category().unwrap(),
+                source.is_adult()
+            ),
+        }
+    }
+}
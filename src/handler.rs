@@ -6,19 +6,85 @@ pub use error::Error;
 pub type Result<T> = error::Result<T>;
 
 mod constants;
-pub use constants::Source;
+pub use constants::{Source, SourceCategory};
 
 mod sauce;
 pub use sauce::Sauce;
 
+mod source_data;
+pub use source_data::{
+    AnimeData, BooruData, DeviantArtData, EHentaiData, FurAffinityData, MangaData, PixivData,
+    SkebData, SourceData, TwitterData,
+};
+
+mod content_filter;
+pub use content_filter::ContentFilter;
+
+mod db_mask;
+pub use db_mask::DbMask;
+
 mod deserialize;
-use deserialize::SauceResult;
+use deserialize::{SauceJSON, SauceResult};
+
+mod input;
+pub use input::SearchInput;
+
+mod response;
+pub use response::{IndexStatus, SearchMeta, SearchResponse, SearchWarning};
+
+mod options;
+pub use options::SearchOptions;
 
-use reqwest::Client;
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
-use std::sync::Mutex;
+mod rate_limit;
+use rate_limit::{Limits, RateLimiter};
+
+mod retry;
+pub use retry::RetryPolicy;
+
+mod threshold;
+pub use threshold::SimilarityThreshold;
+
+mod transport;
+pub use transport::{
+    BoxError, BoxFuture, ReqwestTransport, Transport, TransportRequest, TransportResponse, Upload,
+};
+
+use reqwest::header::{HeaderMap, CONTENT_TYPE};
+use reqwest::{Client, Proxy};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt};
 use url::Url;
 
+/// Checks that a number of results is within the range SauceNAO accepts.
+fn validate_num_results(num_results: u32) -> Result<()> {
+    if (constants::MIN_NUM_RESULTS..=constants::MAX_NUM_RESULTS).contains(&num_results) {
+        Ok(())
+    } else {
+        Err(Error::InvalidParameters(format!(
+            "num_results must be between {} and {}, got {}",
+            constants::MIN_NUM_RESULTS,
+            constants::MAX_NUM_RESULTS,
+            num_results
+        )))
+    }
+}
+
+/// Checks that a minimum similarity is a percentage.
+fn validate_min_similarity(min_similarity: f64) -> Result<()> {
+    if (constants::MIN_SIMILARITY..=constants::MAX_SIMILARITY).contains(&min_similarity) {
+        Ok(())
+    } else {
+        Err(Error::InvalidParameters(format!(
+            "min_similarity must be between {} and {}, got {}",
+            constants::MIN_SIMILARITY,
+            constants::MAX_SIMILARITY,
+            min_similarity
+        )))
+    }
+}
+
 /// A builder to create a Handler for RustNAO usage.
 /// ## Example
 /// ```
@@ -29,12 +95,25 @@ use url::Url;
 pub struct HandlerBuilder {
     api_key: Option<String>,
     testmode: Option<bool>,
-    db_mask: Option<Vec<Source>>,
-    db_mask_i: Option<Vec<Source>>,
+    db_mask: Option<DbMask>,
+    db_mask_i: Option<DbMask>,
     db: Option<u32>,
     num_results: Option<u32>,
     min_similarity: Option<f64>,
+    similarity_threshold: Option<SimilarityThreshold>,
     empty_filter_enabled: Option<bool>,
+    content_filter: Option<ContentFilter>,
+    base_url: Option<String>,
+    client: Option<Client>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    proxy: Option<Proxy>,
+    user_agent: Option<String>,
+    default_headers: Option<HeaderMap>,
+    transport: Option<Arc<dyn Transport>>,
+    rate_limit: Option<bool>,
+    retry_policy: Option<RetryPolicy>,
 }
 
 impl HandlerBuilder {
@@ -50,15 +129,15 @@ impl HandlerBuilder {
         self
     }
 
-    /// Sets which database indices you want included on search for the Handler.
-    pub fn db_mask(&mut self, db_mask: Vec<Source>) -> &mut HandlerBuilder {
-        self.db_mask = Some(db_mask);
+    /// Sets which database indices you want included on search for the Handler.  This takes a [`DbMask`], or anything that converts into one, such as a ``Vec<Source>``.
+    pub fn db_mask<M: Into<DbMask>>(&mut self, db_mask: M) -> &mut HandlerBuilder {
+        self.db_mask = Some(db_mask.into());
         self
     }
 
-    /// Sets which database indices you want excluded on search for the Handler.
-    pub fn db_mask_i(&mut self, db_mask_i: Vec<Source>) -> &mut HandlerBuilder {
-        self.db_mask_i = Some(db_mask_i);
+    /// Sets which database indices you want excluded on search for the Handler.  This takes a [`DbMask`], or anything that converts into one, such as a ``Vec<Source>``.
+    pub fn db_mask_i<M: Into<DbMask>>(&mut self, db_mask_i: M) -> &mut HandlerBuilder {
+        self.db_mask_i = Some(db_mask_i.into());
         self
     }
 
@@ -80,68 +159,268 @@ impl HandlerBuilder {
         self
     }
 
+    /// Sets how the minimum similarity that results are filtered with is decided for the Handler, either the set one, the one suggested by SauceNAO, or whichever is higher.  By default, only the set one is used.
+    pub fn similarity_threshold(
+        &mut self, similarity_threshold: SimilarityThreshold,
+    ) -> &mut HandlerBuilder {
+        self.similarity_threshold = Some(similarity_threshold);
+        self
+    }
+
     /// Sets whether to enable an empty filter by default for the Handler.
     pub fn empty_filter_enabled(&mut self, empty_filter_enabled: bool) -> &mut HandlerBuilder {
         self.empty_filter_enabled = Some(empty_filter_enabled);
         self
     }
 
+    /// Sets how SauceNAO should filter out explicit results by default for the Handler.  By default, SauceNAO's own default is used, which shows all results.
+    pub fn content_filter(&mut self, content_filter: ContentFilter) -> &mut HandlerBuilder {
+        self.content_filter = Some(content_filter);
+        self
+    }
+
+    /// Sets the base URL of the search endpoint for the Handler, such as a local mock or a caching proxy.  Defaults to ``https://saucenao.com/search.php``.
+    pub fn base_url(&mut self, base_url: &str) -> &mut HandlerBuilder {
+        self.base_url = Some(base_url.to_string());
+        self
+    }
+
+    /// Sets a pre-built ``reqwest::Client`` to make requests with for the Handler.  This cannot be combined with the other HTTP settings on the builder, which should be configured on the client itself instead.
+    /// ## Example
+    /// ```
+    /// use rustnao::HandlerBuilder;
+    /// let client = rustnao::reqwest::Client::new();
+    /// let handle = HandlerBuilder::default().api_key("your_api_key").client(client).build();
+    /// ```
+    pub fn client(&mut self, client: Client) -> &mut HandlerBuilder {
+        self.client = Some(client);
+        self
+    }
+
+    /// Sets the total timeout of a request for the Handler, from connecting until the response body has been read.
+    /// ## Example
+    /// ```
+    /// use rustnao::HandlerBuilder;
+    /// use std::time::Duration;
+    /// let handle = HandlerBuilder::default().api_key("your_api_key").timeout(Duration::from_secs(30)).build();
+    /// ```
+    pub fn timeout(&mut self, timeout: Duration) -> &mut HandlerBuilder {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout for connecting to the API for the Handler.
+    pub fn connect_timeout(&mut self, connect_timeout: Duration) -> &mut HandlerBuilder {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Sets the timeout for each read of the response for the Handler.
+    pub fn read_timeout(&mut self, read_timeout: Duration) -> &mut HandlerBuilder {
+        self.read_timeout = Some(read_timeout);
+        self
+    }
+
+    /// Sets a proxy that all requests are sent through for the Handler.
+    pub fn proxy(&mut self, proxy: Proxy) -> &mut HandlerBuilder {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Sets the ``User-Agent`` header sent with requests for the Handler.
+    pub fn user_agent(&mut self, user_agent: &str) -> &mut HandlerBuilder {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// Sets headers that are sent with every request for the Handler.
+    pub fn default_headers(&mut self, default_headers: HeaderMap) -> &mut HandlerBuilder {
+        self.default_headers = Some(default_headers);
+        self
+    }
+
+    /// Sets a custom transport that sends the requests for the Handler, instead of the default reqwest one.  This cannot be combined with a client or the other HTTP settings on the builder.
+    pub fn transport<T: Transport + 'static>(&mut self, transport: T) -> &mut HandlerBuilder {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Sets whether searches should be kept within the SauceNAO search limits for the Handler.  If enabled, searches wait for the short (30 second) limit to reset once it has been used up, and fail with an ``Error::LongLimitReached`` while the long (daily) limit is used up.
+    /// ## Example
+    /// ```
+    /// use rustnao::HandlerBuilder;
+    /// let handle = HandlerBuilder::default().api_key("your_api_key").rate_limit(true).build();
+    /// ```
+    pub fn rate_limit(&mut self, rate_limit: bool) -> &mut HandlerBuilder {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// Sets a policy for retrying searches that fail for transient reasons for the Handler, such as network errors or SauceNAO being overloaded.  By default, searches are not retried.
+    pub fn retry_policy(&mut self, retry_policy: RetryPolicy) -> &mut HandlerBuilder {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Builds the transport used by the Handler, either the one that was given or a reqwest one created from the client and HTTP settings.
+    fn build_transport(&self) -> Result<Arc<dyn Transport>> {
+        let has_settings = self.timeout.is_some()
+            || self.connect_timeout.is_some()
+            || self.read_timeout.is_some()
+            || self.proxy.is_some()
+            || self.user_agent.is_some()
+            || self.default_headers.is_some();
+
+        if let Some(transport) = &self.transport {
+            if has_settings || self.client.is_some() {
+                return Err(Error::InvalidParameters(
+                    "a client or HTTP settings cannot be combined with a custom transport"
+                        .to_string(),
+                ));
+            }
+            return Ok(transport.clone());
+        }
+
+        if let Some(client) = &self.client {
+            if has_settings {
+                return Err(Error::InvalidParameters(
+                    "HTTP settings cannot be combined with a pre-built client, configure the client instead".to_string(),
+                ));
+            }
+            return Ok(Arc::new(ReqwestTransport::new(client.clone())));
+        }
+
+        let mut builder = Client::builder();
+        if let Some(x) = self.timeout {
+            builder = builder.timeout(x);
+        }
+        if let Some(x) = self.connect_timeout {
+            builder = builder.connect_timeout(x);
+        }
+        if let Some(x) = self.read_timeout {
+            builder = builder.read_timeout(x);
+        }
+        if let Some(x) = &self.proxy {
+            builder = builder.proxy(x.clone());
+        }
+        if let Some(x) = &self.user_agent {
+            builder = builder.user_agent(x.as_str());
+        }
+        if let Some(x) = &self.default_headers {
+            builder = builder.default_headers(x.clone());
+        }
+        Ok(Arc::new(ReqwestTransport::new(builder.build()?)))
+    }
+
     /// Builds the HandlerBuilder, returning a Handler that can be used to search.
+    ///
+    /// An invalid number of results or minimum similarity is not checked here, and makes every search that does not override it fail with ``Error::InvalidParameters`` instead.
+    ///
+    /// ## Panics
+    /// Panics if the Handler cannot be created, such as with an unparseable base URL, or a client set along with HTTP settings.  Use [`try_build`](HandlerBuilder::try_build) to handle this instead.
     pub fn build(&self) -> Handler {
+        match self.new_handler() {
+            Ok(handler) => handler,
+            Err(err) => panic!("failed to build the Handler: {}", err),
+        }
+    }
+
+    /// Builds the HandlerBuilder, returning a Result containing either a Handler that can be used to search, or an error if the configuration is invalid, including a number of results or minimum similarity out of range.
+    /// ## Example
+    /// ```
+    /// use rustnao::HandlerBuilder;
+    /// let handle = HandlerBuilder::default().base_url("http://localhost:8080/search.php").try_build();
+    /// assert!(handle.is_ok());
+    /// let invalid = HandlerBuilder::default().base_url("ftp://localhost/search.php").try_build();
+    /// assert!(invalid.is_err());
+    /// ```
+    pub fn try_build(&self) -> Result<Handler> {
+        if let Some(x) = self.num_results {
+            validate_num_results(x)?;
+        }
+        if let Some(x) = self.min_similarity {
+            validate_min_similarity(x)?;
+        }
+        self.new_handler()
+    }
+
+    /// Creates the Handler, failing only if the base URL or transport cannot be created.
+    fn new_handler(&self) -> Result<Handler> {
         let api_key = self.api_key.as_deref().unwrap_or("");
         let testmode = self.testmode.map(|x| if x { 1 } else { 0 });
+        let base_url = Url::parse(self.base_url.as_deref().unwrap_or(constants::API_URL))?;
+        if !(base_url.scheme() == "https" || base_url.scheme() == "http") {
+            return Err(Error::InvalidParameters(format!(
+                "base URL must use http or https, got scheme \"{}\"",
+                base_url.scheme()
+            )));
+        }
+
+        let transport = self.build_transport()?;
 
         let result = Handler::new(
             api_key,
+            base_url,
+            transport,
             testmode,
             self.db_mask.clone(),
             self.db_mask_i.clone(),
             self.db,
             self.num_results,
+            self.rate_limit.unwrap_or(false),
+            self.retry_policy,
         );
         if let Some(x) = self.min_similarity {
             result.set_min_similarity(x);
         }
+        if let Some(x) = self.similarity_threshold {
+            result.set_similarity_threshold(x);
+        }
         if let Some(x) = self.empty_filter_enabled {
             result.set_empty_filter(x);
         }
-        result
+        if let Some(x) = self.content_filter {
+            result.set_content_filter(x);
+        }
+        Ok(result)
     }
 }
 
+/// The filters applied to the results of a single search, with the Handler's defaults filled in.
+#[derive(Debug, Clone, Copy)]
+struct ResultFilters {
+    min_similarity: f64,
+    similarity_threshold: SimilarityThreshold,
+    empty_filter_enabled: bool,
+}
+
 /// A handler struct to make SauceNAO API calls with.
 #[derive(Debug)]
 pub struct Handler {
     api_key: String,
+    base_url: Url,
     output_type: i32,
     testmode: Option<u32>,
-    db_mask: Option<Vec<Source>>,
-    db_mask_i: Option<Vec<Source>>,
+    db_mask: Option<DbMask>,
+    db_mask_i: Option<DbMask>,
     db: Option<u32>,
     num_results: Option<u32>,
-    short_limit: AtomicU32,
-    long_limit: AtomicU32,
-    short_left: AtomicU32,
-    long_left: AtomicU32,
+    limits: Limits,
+    rate_limiter: Option<RateLimiter>,
+    retry_policy: Option<RetryPolicy>,
     min_similarity: Mutex<f64>,
+    similarity_threshold: Mutex<SimilarityThreshold>,
     empty_filter_enabled: AtomicBool,
-    client: Client,
+    content_filter: Mutex<Option<ContentFilter>>,
+    transport: Arc<dyn Transport>,
 }
 
 impl Handler {
-    /// Generates a bitmask from a given vector of Sources.
-    fn generate_bitmask(&self, mask: Vec<Source>) -> u64 {
-        let mut res: u64 = 0;
-        for m in mask {
-            let index = m as u32;
-            res |= 1 << index;
-        }
-        res
-    }
-
-    /// Generates a url from the given image path/url
-    fn generate_url(&self, image_path: &str, num_results: Option<u32>) -> Result<String> {
-        let mut request_url = Url::parse(constants::API_URL)?;
+    /// Generates a url, passing along the image url if the image is not being uploaded
+    fn generate_url(
+        &self, image_url: Option<&str>, options: &SearchOptions, num_results: u32,
+    ) -> Result<Url> {
+        let mut request_url = self.base_url.clone();
         request_url
             .query_pairs_mut()
             .append_pair("api_key", self.api_key.as_str());
@@ -149,188 +428,522 @@ impl Handler {
             .query_pairs_mut()
             .append_pair("output_type", self.output_type.to_string().as_str());
 
-        if let Some(val) = self.db {
+        if let Some(val) = options.db.or(self.db) {
             request_url
                 .query_pairs_mut()
                 .append_pair("db", val.to_string().as_str());
         }
 
-        if let Some(val) = &self.db_mask {
+        if let Some(val) = options.db_mask.as_ref().or(self.db_mask.as_ref()) {
             if !val.is_empty() {
-                request_url.query_pairs_mut().append_pair(
-                    "dbmask",
-                    self.generate_bitmask(val.clone()).to_string().as_str(),
-                );
+                request_url
+                    .query_pairs_mut()
+                    .append_pair("dbmask", val.to_string().as_str());
             }
         }
-        if let Some(val) = &self.db_mask_i {
+        if let Some(val) = options.db_mask_i.as_ref().or(self.db_mask_i.as_ref()) {
             if !val.is_empty() {
-                request_url.query_pairs_mut().append_pair(
-                    "dbmaski",
-                    self.generate_bitmask(val.clone()).to_string().as_str(),
-                );
+                request_url
+                    .query_pairs_mut()
+                    .append_pair("dbmaski", val.to_string().as_str());
             }
         }
 
-        request_url.query_pairs_mut().append_pair(
-            "testmode",
-            self.testmode.unwrap_or(0).to_string().as_str(),
-        );
+        let testmode = options.testmode.map(u32::from).or(self.testmode);
+        request_url
+            .query_pairs_mut()
+            .append_pair("testmode", testmode.unwrap_or(0).to_string().as_str());
 
-        let res_count = num_results.or(self.num_results).unwrap_or(999);
         request_url
             .query_pairs_mut()
-            .append_pair("numres", res_count.to_string().as_str());
+            .append_pair("numres", num_results.to_string().as_str());
+
+        let mut content_filter = options.content_filter;
+        if content_filter.is_none() {
+            if let Ok(lock) = self.content_filter.lock() {
+                content_filter = *lock;
+            }
+        }
+        if let Some(val) = content_filter {
+            request_url
+                .query_pairs_mut()
+                .append_pair("hide", val.value().to_string().as_str());
+        }
 
-        if image_path.starts_with("https://") || image_path.starts_with("http://") {
-            request_url.query_pairs_mut().append_pair("url", image_path);
+        if let Some(val) = image_url {
+            request_url.query_pairs_mut().append_pair("url", val);
         }
 
-        Ok(request_url.to_string())
+        Ok(request_url)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn new(
-        api_key: &str, testmode: Option<u32>, db_mask: Option<Vec<Source>>,
-        db_mask_i: Option<Vec<Source>>, db: Option<u32>, num_results: Option<u32>,
+        api_key: &str, base_url: Url, transport: Arc<dyn Transport>, testmode: Option<u32>,
+        db_mask: Option<DbMask>, db_mask_i: Option<DbMask>, db: Option<u32>,
+        num_results: Option<u32>, rate_limit: bool, retry_policy: Option<RetryPolicy>,
     ) -> Handler {
         Handler {
             api_key: api_key.to_string(),
+            base_url,
             output_type: 2,
             testmode,
             db_mask,
             db_mask_i,
             db,
             num_results,
-            short_limit: AtomicU32::new(12),
-            long_limit: AtomicU32::new(200),
-            short_left: AtomicU32::new(12),
-            long_left: AtomicU32::new(200),
+            limits: Limits::default(),
+            rate_limiter: if rate_limit {
+                Some(RateLimiter::default())
+            } else {
+                None
+            },
+            retry_policy,
             min_similarity: Mutex::new(0.0),
+            similarity_threshold: Mutex::new(SimilarityThreshold::default()),
             empty_filter_enabled: AtomicBool::new(false),
-            client: Client::new(),
+            content_filter: Mutex::new(None),
+            transport,
         }
     }
 
     /// Sets the minimum similarity threshold.
+    ///
+    /// The value is not checked here, so a value outside of 0 to 100 (or NaN) makes every later search that does not override it fail with ``Error::InvalidParameters``.  Use [`try_set_min_similarity`](Handler::try_set_min_similarity) to reject it immediately instead.
     pub fn set_min_similarity<T: Into<f64>>(&self, min_similarity: T) {
         if let Ok(mut lock) = self.min_similarity.lock() {
             *lock = min_similarity.into();
         }
     }
 
+    /// Sets the minimum similarity threshold, returning an error and leaving the current threshold unchanged if it is not between 0 and 100.
+    /// ## Example
+    /// ```
+    /// use rustnao::HandlerBuilder;
+    /// let handle = HandlerBuilder::default().api_key("your_api_key").build();
+    /// assert!(handle.try_set_min_similarity(45).is_ok());
+    /// assert!(handle.try_set_min_similarity(100.1).is_err());
+    /// ```
+    pub fn try_set_min_similarity<T: Into<f64>>(&self, min_similarity: T) -> Result<()> {
+        let min_similarity = min_similarity.into();
+        validate_min_similarity(min_similarity)?;
+        self.set_min_similarity(min_similarity);
+        Ok(())
+    }
+
+    /// Sets how the minimum similarity that results are filtered with is decided.
+    pub fn set_similarity_threshold(&self, similarity_threshold: SimilarityThreshold) {
+        if let Ok(mut lock) = self.similarity_threshold.lock() {
+            *lock = similarity_threshold;
+        }
+    }
+
     /// Sets whether empty URL results should be filtered.
     pub fn set_empty_filter(&self, enabled: bool) {
         self.empty_filter_enabled.store(enabled, Ordering::SeqCst);
     }
 
+    /// Sets how SauceNAO should filter out explicit results.
+    pub fn set_content_filter(&self, content_filter: ContentFilter) {
+        if let Ok(mut lock) = self.content_filter.lock() {
+            *lock = Some(content_filter);
+        }
+    }
+
     /// Gets the remaining short limit.
     pub fn get_current_short_limit(&self) -> u32 {
-        self.short_left.load(Ordering::SeqCst)
+        self.limits.short_left.load(Ordering::SeqCst)
     }
 
     /// Gets the remaining long limit.
     pub fn get_current_long_limit(&self) -> u32 {
-        self.long_left.load(Ordering::SeqCst)
+        self.limits.long_left.load(Ordering::SeqCst)
     }
 
     fn process_results(
-        &self, returned_sauce: SauceResult, min_similarity: Option<f64>,
-    ) -> Result<Vec<Sauce>> {
+        &self, returned_sauce: SauceResult, filters: ResultFilters,
+    ) -> Result<SearchResponse> {
+        let header = returned_sauce.header;
+
+        // A positive status means some indices failed on SauceNAO's end, which is only a partial
+        // failure as long as results were still returned.
+        let server_failed = header.status > 0 && returned_sauce.results.is_none();
+        if header.status < 0 || server_failed {
+            return Err(Error::from_status(
+                header.status,
+                header.message.unwrap_or_default(),
+            ));
+        }
+
+        let mut warnings = Vec::new();
+        let limits = [
+            ("short_limit", header.short_limit),
+            ("long_limit", header.long_limit),
+            ("short_remaining", header.short_remaining),
+            ("long_remaining", header.long_remaining),
+        ];
+        for (name, value) in limits {
+            if value.is_none() {
+                warnings.push(SearchWarning {
+                    result: None,
+                    message: format!("{} is missing or not a number", name),
+                });
+            }
+        }
+
+        self.limits.update(
+            header.short_limit,
+            header.long_limit,
+            header.short_remaining,
+            header.long_remaining,
+        );
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.record(
+                self.limits.short_left.load(Ordering::SeqCst),
+                self.limits.long_left.load(Ordering::SeqCst),
+            );
+        }
+
+        let min_similarity = filters
+            .similarity_threshold
+            .resolve(filters.min_similarity, header.minimum_similarity);
+
         let mut ret_sauce: Vec<Sauce> = Vec::new();
+        for (i, res) in returned_sauce.results.into_iter().flatten().enumerate() {
+            match Self::read_result(res, min_similarity, filters.empty_filter_enabled) {
+                Ok(Some(sauce)) => ret_sauce.push(sauce),
+                Ok(None) => {}
+                Err(message) => warnings.push(SearchWarning {
+                    result: Some(i),
+                    message,
+                }),
+            }
+        }
+
+        Ok(SearchResponse {
+            meta: response::new_meta(header),
+            results: ret_sauce,
+            warnings,
+        })
+    }
+
+    /// Reads a single result, returning ``None`` if it is filtered out, or what was wrong with it if it could not be read.
+    fn read_result(
+        res: serde_json::Value, min_similarity: f64, empty_filter_enabled: bool,
+    ) -> std::result::Result<Option<Sauce>, String> {
+        let res: SauceJSON =
+            serde_json::from_value(res).map_err(|e| format!("could not be read: {}", e))?;
+        let sauce_min_sim = res
+            .header
+            .similarity
+            .ok_or("similarity is missing or not a number")?;
+        let index_id = res
+            .header
+            .index_id
+            .ok_or("index_id is missing or not a number")?;
+        let ext_urls = res.data.ext_urls.unwrap_or_default();
+
+        if sauce_min_sim < min_similarity || (empty_filter_enabled && ext_urls.is_empty()) {
+            return Ok(None);
+        }
+
+        let source = Source::from_u32(index_id);
+        let site_name = source
+            .map(|s| s.name().to_string())
+            .or(res.header.index_name)
+            .unwrap_or_default();
+
+        Ok(Some(sauce::new_sauce(
+            ext_urls,
+            res.data.title,
+            site_name,
+            index_id,
+            index_id, // SauceNAO returns index_id
+            sauce_min_sim as f32,
+            res.header.thumbnail.unwrap_or_default(),
+            serde_json::to_value(&res.data.additional_fields).ok(),
+            res.data.source,
+            res.data.creator,
+            res.data.eng_name,
+            res.data.jp_name,
+        )))
+    }
+
+    /// Resolves a SearchInput into either an image URL to pass along, or an image to upload.
+    async fn resolve_input(input: SearchInput) -> Result<(Option<Url>, Option<Upload>)> {
+        let path = match input {
+            SearchInput::Url(url) => match url.scheme() {
+                "http" | "https" => return Ok((Some(url), None)),
+                "file" => url.to_file_path().map_err(|_| {
+                    Error::InvalidParameters(format!("\"{}\" is not a valid file URL", url))
+                })?,
+                scheme => {
+                    return Err(Error::InvalidParameters(format!(
+                        "unsupported URL scheme \"{}\", only http, https and file are supported",
+                        scheme
+                    )))
+                }
+            },
+            SearchInput::Path(path) => path,
+            SearchInput::Bytes { data, file_name } => {
+                return Ok((
+                    None,
+                    Some(Upload {
+                        file_name,
+                        data: data.into(),
+                    }),
+                ))
+            }
+            SearchInput::Reader {
+                mut reader,
+                file_name,
+            } => {
+                let mut data = Vec::new();
+                reader.read_to_end(&mut data).await?;
+                return Ok((
+                    None,
+                    Some(Upload {
+                        file_name,
+                        data: data.into(),
+                    }),
+                ));
+            }
+        };
+
+        let data = tokio::fs::read(&path).await?;
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(input::DEFAULT_FILE_NAME)
+            .to_string();
+        Ok((
+            None,
+            Some(Upload {
+                file_name,
+                data: data.into(),
+            }),
+        ))
+    }
+
+    /// Asynchronously returns a Result of either a SearchResponse, holding the Sauce objects along with the metadata SauceNAO returned.  Any options that are set override the Handler's defaults for this search only.
+    ///
+    /// The image can be anything that converts into a [`SearchInput`], such as a URL or path string, a ``Url``, a ``Path``, or a ``Vec<u8>``.  Local files are read without blocking the executor.
+    ///
+    /// The number of results must be between 1 and 999, and the minimum similarity between 0 and 100, otherwise an ``Error::InvalidParameters`` is returned without making a request.
+    ///
+    /// A minimum similarity given for this search is always applied, even with ``SimilarityThreshold::ServerSuggested``, in which case the higher of it and SauceNAO's suggestion is used.
+    /// ## Example
+    /// ```no_run
+    /// use rustnao::{HandlerBuilder, SearchOptions, Source};
+    /// # tokio_test::block_on(async {
+    /// let handle = HandlerBuilder::default().api_key("your_api_key").build();
+    /// let mut options = SearchOptions::default();
+    /// options.db_mask(vec![Source::Pixiv]).num_results(3);
+    /// let response = handle.search("https://i.imgur.com/W42kkKS.jpg", &options).await.unwrap();
+    /// for sauce in response.results {
+    ///     println!("{:?}", sauce);
+    /// }
+    /// # });
+    /// ```
+    pub async fn search<I: Into<SearchInput>>(
+        &self, image: I, options: &SearchOptions,
+    ) -> Result<SearchResponse> {
+        let num_results = options
+            .num_results
+            .or(self.num_results)
+            .unwrap_or(constants::MAX_NUM_RESULTS);
+        validate_num_results(num_results)?;
+        let mut min_similarity = constants::MIN_SIMILARITY;
+        if let Some(val) = options.min_similarity {
+            min_similarity = val;
+        } else if let Ok(lock) = self.min_similarity.lock() {
+            min_similarity = *lock;
+        }
+        validate_min_similarity(min_similarity)?;
+        let mut similarity_threshold = SimilarityThreshold::default();
+        if let Some(val) = options.similarity_threshold {
+            similarity_threshold = val;
+        } else if let Ok(lock) = self.similarity_threshold.lock() {
+            similarity_threshold = *lock;
+        }
+        // A minimum similarity given for this search is a floor that the suggestion cannot lower.
+        let similarity_threshold = match (similarity_threshold, options.min_similarity) {
+            (SimilarityThreshold::ServerSuggested, Some(_)) => SimilarityThreshold::Max,
+            (x, _) => x,
+        };
+        let filters = ResultFilters {
+            min_similarity,
+            similarity_threshold,
+            empty_filter_enabled: options
+                .empty_filter_enabled
+                .unwrap_or_else(|| self.empty_filter_enabled.load(Ordering::SeqCst)),
+        };
 
-        if returned_sauce.header.status >= 0 {
-            self.short_left
-                .store(returned_sauce.header.short_remaining, Ordering::SeqCst);
-            self.long_left
-                .store(returned_sauce.header.long_remaining, Ordering::SeqCst);
-            self.short_limit
-                .store(returned_sauce.header.short_limit.parse()?, Ordering::SeqCst);
-            self.long_limit
-                .store(returned_sauce.header.long_limit.parse()?, Ordering::SeqCst);
-
-            if let Some(results) = returned_sauce.results {
-                let actual_min_sim =
-                    min_similarity.unwrap_or_else(|| *self.min_similarity.lock().unwrap());
-                
-                for res in results {
-                    let sauce_min_sim: f64 = res.header.similarity.parse()?;
-                    if sauce_min_sim >= actual_min_sim
-                        && (!self.empty_filter_enabled.load(Ordering::SeqCst)
-                            || !res.data.ext_urls.is_empty())
-                    {
-                        let source = Source::from_u32(res.header.index_id);
-                        let site_name = source.map(|s| s.name()).unwrap_or(&res.header.index_name).to_string();
-
-                        ret_sauce.push(sauce::new_sauce(
-                            res.data.ext_urls,
-                            res.data.title,
-                            site_name,
-                            res.header.index_id,
-                            res.header.index_id, // SauceNAO returns index_id
-                            sauce_min_sim as f32,
-                            res.header.thumbnail,
-                            match serde_json::to_value(&res.data.additional_fields) {
-                                Ok(x) => Some(x),
-                                Err(_) => None,
-                            },
-                            res.data.source,
-                            res.data.creator,
-                            res.data.eng_name,
-                            res.data.jp_name,
-                        ));
-                    }
+        let (image_url, upload) = Self::resolve_input(image.into()).await?;
+        let url = self.generate_url(image_url.as_ref().map(Url::as_str), options, num_results)?;
+        let request = TransportRequest { url, upload };
+
+        let mut attempt = 1;
+        loop {
+            let result = self.send_request(request.clone(), filters).await;
+            match (&result, self.retry_policy) {
+                (Err(err), Some(policy)) if policy.should_retry(err, attempt) => {
+                    tokio::time::sleep(policy.delay(err, attempt)).await;
+                    attempt += 1;
                 }
+                _ => return result,
             }
-            Ok(ret_sauce)
-        } else {
-            Err(Error::InvalidCode {
-                code: returned_sauce.header.status,
-                message: returned_sauce.header.message,
-            })
+        }
+    }
+
+    /// Returns SearchOptions overriding only the number of results and minimum similarity, as taken by the ``get_sauce`` methods.
+    fn legacy_options(num_results: Option<u32>, min_similarity: Option<f64>) -> SearchOptions {
+        SearchOptions {
+            num_results,
+            min_similarity,
+            ..SearchOptions::default()
         }
     }
 
     /// Asynchronously returns a Result of either a vector of Sauce objects.
-    pub async fn get_sauce(
-        &self, image_path: &str, num_results: Option<u32>, min_similarity: Option<f64>,
+    ///
+    /// This is a shorthand for [`Handler::search`] that only overrides the number of results and minimum similarity.
+    pub async fn get_sauce<I: Into<SearchInput>>(
+        &self, image: I, num_results: Option<u32>, min_similarity: Option<f64>,
     ) -> Result<Vec<Sauce>> {
-        let url_string = self.generate_url(image_path, num_results)?;
-
-        let response = if !(image_path.starts_with("https://") || image_path.starts_with("http://")) {
-            let data = std::fs::read(image_path)?;
-            let file_name = std::path::Path::new(image_path)
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("image.jpg")
-                .to_string();
-            let part = reqwest::multipart::Part::bytes(data).file_name(file_name);
-            let form = reqwest::multipart::Form::new().part("file", part);
-            self.client.post(&url_string).multipart(form).send().await?
-        } else {
-            self.client.post(&url_string).send().await?
-        };
+        self.get_sauce_response(image, num_results, min_similarity)
+            .await
+            .map(|response| response.results)
+    }
+
+    /// Asynchronously returns a Result of either a SearchResponse, holding the Sauce objects along with the metadata SauceNAO returned, such as its suggested minimum similarity and the type of account used.
+    ///
+    /// This is a shorthand for [`Handler::search`] that only overrides the number of results and minimum similarity.
+    /// ## Example
+    /// ```no_run
+    /// use rustnao::HandlerBuilder;
+    /// # tokio_test::block_on(async {
+    /// let handle = HandlerBuilder::default().api_key("your_api_key").build();
+    /// let response = handle.get_sauce_response("https://i.imgur.com/W42kkKS.jpg", None, None).await.unwrap();
+    /// if !response.meta.is_premium() {
+    ///     println!("{:?} searches left today", response.meta.long_remaining);
+    /// }
+    /// # });
+    /// ```
+    pub async fn get_sauce_response<I: Into<SearchInput>>(
+        &self, image: I, num_results: Option<u32>, min_similarity: Option<f64>,
+    ) -> Result<SearchResponse> {
+        self.search(image, &Self::legacy_options(num_results, min_similarity))
+            .await
+    }
+
+    /// Parses a raw response, returning a specific error if it is an HTTP error or not JSON.
+    fn parse_response(response: &TransportResponse) -> Result<SauceResult> {
+        let parsed = serde_json::from_slice::<SauceResult>(&response.body);
+
+        if response.status.is_success() {
+            let is_json = response
+                .headers
+                .get(CONTENT_TYPE)
+                .and_then(|x| x.to_str().ok())
+                .is_none_or(|x| x.contains("json"));
+            return match parsed {
+                Ok(returned_sauce) => Ok(returned_sauce),
+                Err(err) if is_json => Err(err.into()),
+                Err(_) => Err(Error::from_response(response)),
+            };
+        }
+
+        // SauceNAO usually explains HTTP errors with its own status code and message.
+        match parsed {
+            Ok(returned_sauce) if returned_sauce.header.status != 0 => Err(Error::from_status(
+                returned_sauce.header.status,
+                returned_sauce.header.message.unwrap_or_default(),
+            )),
+            _ => Err(Error::from_response(response)),
+        }
+    }
+
+    /// Sends a single search request, waiting on the rate limiter first if it is enabled.
+    async fn send_request(
+        &self, request: TransportRequest, filters: ResultFilters,
+    ) -> Result<SearchResponse> {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(&self.limits).await?;
+        }
+
+        let response = self
+            .transport
+            .send(request)
+            .await
+            .map_err(Error::InvalidRequest)?;
+
+        let result = Self::parse_response(&response)
+            .and_then(|returned_sauce| self.process_results(returned_sauce, filters));
+        if let (Err(err), Some(rate_limiter)) = (&result, &self.rate_limiter) {
+            rate_limiter.record_error(err, &self.limits);
+        }
+        result
+    }
+
+    /// Asynchronously returns a Result of either a vector of Sauce objects, searching for an image that is already in memory.
+    /// ## Example
+    /// ```no_run
+    /// use rustnao::HandlerBuilder;
+    /// # tokio_test::block_on(async {
+    /// let handle = HandlerBuilder::default().api_key("your_api_key").build();
+    /// let data = std::fs::read("./tests/test.jpg").unwrap();
+    /// let result = handle.get_sauce_bytes(&data, "test.jpg", None, None).await;
+    /// # });
+    /// ```
+    pub async fn get_sauce_bytes(
+        &self, data: &[u8], file_name: &str, num_results: Option<u32>, min_similarity: Option<f64>,
+    ) -> Result<Vec<Sauce>> {
+        self.get_sauce(
+            SearchInput::bytes(data, file_name),
+            num_results,
+            min_similarity,
+        )
+        .await
+    }
 
-        let returned_sauce: SauceResult = response.json().await?;
-        self.process_results(returned_sauce, min_similarity)
+    /// Asynchronously returns a Result of either a vector of Sauce objects, searching for an image read from an ``AsyncRead``.
+    /// ## Example
+    /// ```no_run
+    /// use rustnao::HandlerBuilder;
+    /// # tokio_test::block_on(async {
+    /// let handle = HandlerBuilder::default().api_key("your_api_key").build();
+    /// let file = tokio::fs::File::open("./tests/test.jpg").await.unwrap();
+    /// let result = handle.get_sauce_reader(file, "test.jpg", None, None).await;
+    /// # });
+    /// ```
+    pub async fn get_sauce_reader<R: AsyncRead + Unpin>(
+        &self, mut reader: R, file_name: &str, num_results: Option<u32>,
+        min_similarity: Option<f64>,
+    ) -> Result<Vec<Sauce>> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).await?;
+        self.get_sauce(
+            SearchInput::bytes(data, file_name),
+            num_results,
+            min_similarity,
+        )
+        .await
     }
 
     /// Returns a string representing a vector of Sauce objects as a serialized JSON.
-    pub async fn get_sauce_as_json(
-        &self, image_path: &str, num_results: Option<u32>, min_similarity: Option<f64>,
+    pub async fn get_sauce_as_json<I: Into<SearchInput>>(
+        &self, image: I, num_results: Option<u32>, min_similarity: Option<f64>,
     ) -> Result<String> {
-        let ret_sauce = self
-            .get_sauce(image_path, num_results, min_similarity)
-            .await?;
+        let ret_sauce = self.get_sauce(image, num_results, min_similarity).await?;
         Ok(serde_json::to_string(&ret_sauce)?)
     }
 
     /// Returns a string representing a vector of Sauce objects as a serialized pretty JSON.
-    pub async fn get_sauce_as_pretty_json(
-        &self, image_path: &str, num_results: Option<u32>, min_similarity: Option<f64>,
+    pub async fn get_sauce_as_pretty_json<I: Into<SearchInput>>(
+        &self, image: I, num_results: Option<u32>, min_similarity: Option<f64>,
     ) -> Result<String> {
-        let ret_sauce = self
-            .get_sauce(image_path, num_results, min_similarity)
-            .await?;
+        let ret_sauce = self.get_sauce(image, num_results, min_similarity).await?;
         Ok(serde_json::to_string_pretty(&ret_sauce)?)
     }
 }
@@ -350,4 +963,4 @@ impl ToJSON for Vec<Sauce> {
     fn to_json(&self) -> Result<String> {
         Ok(serde_json::to_string(self)?)
     }
-}
\ No newline at end of file
+}
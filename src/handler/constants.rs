@@ -1,176 +1,306 @@
 //! A list of constants and the Source enum used by the RustNAO library.
 //! Constants are pulled from [here](https://saucenao.com/status.html).
 
+use super::error::Error;
+use serde::de::{self, Unexpected, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
 pub const API_URL: &str = "https://saucenao.com/search.php";
+pub const MIN_NUM_RESULTS: u32 = 1;
+pub const MAX_NUM_RESULTS: u32 = 999;
+pub const MIN_SIMILARITY: f64 = 0.0;
+pub const MAX_SIMILARITY: f64 = 100.0;
+pub const MAX_BODY_SNIPPET: usize = 256;
+pub const SHORT_LIMIT_WINDOW: Duration = Duration::from_secs(30);
+pub const LONG_LIMIT_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Defines the Source enum from a table with the variant, index, slug, name, category and whether
+/// it is adult-only of each source.
+macro_rules! sources {
+    ($($variant:ident = $index:literal, $slug:literal, $name:literal, $category:ident, $adult:literal;)*) => {
+        /// A list of all available sources on SauceNAO.  Indices that are not known to rustnao are kept as ``Source::Unknown``.
+        ///
+        /// Variants have no explicit discriminants, since ``Source::Unknown`` holds its index, so ``Source::Pixiv as u32`` does not compile.  Use [`Source::index`] or ``u32::from`` instead.
+        ///
+        /// In human-readable formats such as JSON, sources are serialized as their slug (see [`Source::slug`]), or as their index if they are unknown, and can be deserialized or parsed from their slug, their name, or their index.  In compact formats such as bincode or postcard, which cannot tell a string from a number, sources are always serialized and deserialized as their index.
+        /// ## Example
+        /// ```
+        /// use rustnao::Source;
+        /// assert_eq!("pixiv".parse::<Source>().unwrap(), Source::Pixiv);
+        /// assert_eq!("Danbooru".parse::<Source>().unwrap(), Source::Danbooru);
+        /// assert_eq!("21".parse::<Source>().unwrap(), Source::Anime);
+        /// assert_eq!("500".parse::<Source>().unwrap(), Source::Unknown(500));
+        /// assert_eq!(Source::Yandere.to_string(), "Yande.re");
+        /// ```
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Source {
+            $(
+                #[doc = concat!($name, " (index ", stringify!($index), ")")]
+                $variant,
+            )*
+            /// An index that is not known to rustnao.
+            Unknown(u32),
+        }
+
+        impl Source {
+            /// Every known source, in order of index.
+            const ALL: &'static [Source] = &[$(Source::$variant,)*];
+
+            /// Returns the name of the source as a string, or ``"Unknown"`` if it is not known.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Source::$variant => $name,)*
+                    Source::Unknown(_) => "Unknown",
+                }
+            }
+
+            /// Returns the slug of the source, a stable lowercase identifier used when serializing it, or ``"unknown"`` if it is not known.
+            pub fn slug(&self) -> &'static str {
+                match self {
+                    $(Source::$variant => $slug,)*
+                    Source::Unknown(_) => "unknown",
+                }
+            }
+
+            /// Returns the index of the source on SauceNAO.
+            pub fn index(&self) -> u32 {
+                match self {
+                    $(Source::$variant => $index,)*
+                    Source::Unknown(index) => *index,
+                }
+            }
+
+            /// Returns the category of the source, or ``None`` if it is not known.
+            pub fn category(&self) -> Option<SourceCategory> {
+                match self {
+                    $(Source::$variant => Some(SourceCategory::$category),)*
+                    Source::Unknown(_) => None,
+                }
+            }
+
+            /// Returns whether the source only or predominantly has adult content.  Sources that are not known are not considered adult-only.
+            pub fn is_adult(&self) -> bool {
+                match self {
+                    $(Source::$variant => $adult,)*
+                    Source::Unknown(_) => false,
+                }
+            }
+
+            /// Converts a u32 index to a Source enum, if it is a known index.  Use ``Source::from`` to keep unknown indices as ``Source::Unknown``.
+            pub fn from_u32(index: u32) -> Option<Self> {
+                match index {
+                    $($index => Some(Source::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
 
-/// A list of all available sources on SauceNAO.
+// One row per index, in the order of the index list on SauceNAO's status page
+// (https://saucenao.com/status.html).  The last two columns are the category of the source and
+// whether it is adult-only, which is also set for sources that are predominantly adult, such as
+// Sankaku Channel, e621.net, Idol Complex and Kemono.  `cargo run --example source_table` lists
+// every index a live search reports, printing known ones as their row and unknown ones with the
+// name SauceNAO gives them.
+//
+// Indices left out, which are kept as `Source::Unknown` and shown with the name SauceNAO gives them:
+// - 1, 7 and 14, which SauceNAO's API example marks as reserved.
+// - 17, and indices above 44 whose name could not be checked against a live search, such as the
+//   Seiga manga index.  Run the example above and add a row for each once it is named.
+sources! {
+    HMagazines = 0,         "h-magazines",          "H-Magazines",         Manga,  true;
+    HGameCG = 2,            "h-game-cg",            "H-Game CG",           Other,  true;
+    DoujinshiDB = 3,        "doujinshi-db",         "DoujinshiDB",         Manga,  true;
+    DoujinshiDBSamples = 4, "doujinshi-db-samples", "DoujinshiDB Samples", Manga,  true;
+    Pixiv = 5,              "pixiv",                "pixiv Images",        Art,    false;
+    PixivHistorical = 6,    "pixiv-historical",     "pixiv Historical",    Art,    false;
+    NicoNicoSeiga = 8,      "nico-nico-seiga",      "Nico Nico Seiga",     Art,    false;
+    Danbooru = 9,           "danbooru",             "Danbooru",            Booru,  false;
+    Drawr = 10,             "drawr",                "drawr Images",        Art,    false;
+    Nijie = 11,             "nijie",                "Nijie Images",        Art,    true;
+    Yandere = 12,           "yandere",              "Yande.re",            Booru,  false;
+    OpeningsMoe = 13,       "openings-moe",         "Openings.moe",        Video,  false;
+    Shutterstock = 15,      "shutterstock",         "Shutterstock",        Other,  false;
+    Fakku = 16,             "fakku",                "FAKKU",               Manga,  true;
+    HMisc = 18,             "h-misc",               "H-Misc",              Manga,  true;
+    TwoDMarket = 19,        "2d-market",            "2D-Market",           Manga,  true;
+    MediBang = 20,          "medibang",             "MediBang",            Art,    false;
+    Anime = 21,             "anime",                "Anime",               Video,  false;
+    HAnime = 22,            "h-anime",              "H-Anime",             Video,  true;
+    Movies = 23,            "movies",               "Movies",              Video,  false;
+    Shows = 24,             "shows",                "Shows",               Video,  false;
+    Gelbooru = 25,          "gelbooru",             "Gelbooru",            Booru,  false;
+    Konachan = 26,          "konachan",             "Konachan",            Booru,  false;
+    SankakuChannel = 27,    "sankaku-channel",      "Sankaku Channel",     Booru,  true;
+    AnimePicturesNet = 28,  "anime-pictures",       "Anime-Pictures.net",  Booru,  false;
+    E621Net = 29,           "e621",                 "e621.net",            Booru,  true;
+    IdolComplex = 30,       "idol-complex",         "Idol Complex",        Booru,  true;
+    BcyNetIllust = 31,      "bcy-illust",           "bcy.net Illust",      Art,    false;
+    BcyNetCosplay = 32,     "bcy-cosplay",          "bcy.net Cosplay",     Art,    false;
+    PortalGraphicsNet = 33, "portal-graphics",      "PortalGraphics.net",  Art,    false;
+    DeviantArt = 34,        "deviantart",           "deviantArt",          Art,    false;
+    PawooNet = 35,          "pawoo",                "Pawoo.net",           Social, false;
+    Madokami = 36,          "madokami",             "Madokami",            Manga,  false;
+    MangaDex = 37,          "mangadex",             "MangaDex",            Manga,  false;
+    EHentai = 38,           "e-hentai",             "E-Hentai",            Manga,  true;
+    ArtStation = 39,        "artstation",           "ArtStation",          Art,    false;
+    FurAffinity = 40,       "furaffinity",          "FurAffinity",         Art,    false;
+    Twitter = 41,           "twitter",              "Twitter",             Social, false;
+    FurryNetwork = 42,      "furry-network",        "Furry Network",       Art,    false;
+    Kemono = 43,            "kemono",               "Kemono",              Art,    true;
+    Skeb = 44,              "skeb",                 "Skeb",                Art,    false;
+    MangaDexV2 = 371,       "mangadex-v2",          "MangaDex V2",         Manga,  false;
+}
+
+/// A kind of source, for building masks out of groups of similar sources.
+/// ## Example
+/// ```
+/// use rustnao::{DbMask, HandlerBuilder, SourceCategory};
+/// // Only search boorus.
+/// let handle = HandlerBuilder::default().api_key("your_api_key").db_mask(SourceCategory::Booru).build();
+/// // Search everything besides adult-only sources.
+/// let handle = HandlerBuilder::default().api_key("your_api_key").db_mask_i(DbMask::adult()).build();
+/// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum Source {
-    /// H-Magazines (index 0)
-    HMagazines = 0,
-    /// H-Game CG (index 2)
-    HGameCG = 2,
-    /// DoujinshiDB (index 3)
-    DoujinshiDB = 3,
-    /// Pixiv (index 5)
-    Pixiv = 5,
-    /// Nico Nico Seiga (index 8)
-    NicoNicoSeiga = 8,
-    /// Danbooru (index 9)
-    Danbooru = 9,
-    /// drawr Images (index 10)
-    Drawr = 10,
-    /// Nijie Images (index 11)
-    Nijie = 11,
-    /// Yand.ere (index 12)
-    Yandere = 12,
-    /// Shutterstock (index 15)
-    Shutterstock = 15,
-    /// Fakku (index 16)
-    Fakku = 16,
-    /// H-Misc (index 18)
-    HMisc = 18,
-    /// 2D-Market (index 19)
-    TwoDMarket = 19,
-    /// MediBang (index 20)
-    MediBang = 20,
-    /// Anime (index 21)
-    Anime = 21,
-    /// H-Anime (index 22)
-    HAnime = 22,
-    /// Movies (index 23)
-    Movies = 23,
-    /// Shows (index 24)
-    Shows = 24,
-    /// Gelbooru (index 25)
-    Gelbooru = 25,
-    /// Konachan (index 26)
-    Konachan = 26,
-    /// Sankaku Channel (index 27)
-    SankakuChannel = 27,
-    /// Anime-Pictures.net (index 28)
-    AnimePicturesNet = 28,
-    /// e621.net (index 29)
-    E621Net = 29,
-    /// Idol Complex (index 30)
-    IdolComplex = 30,
-    /// bcy.net Illust (index 31)
-    BcyNetIllust = 31,
-    /// bcy.net Cosplay (index 32)
-    BcyNetCosplay = 32,
-    /// PortalGraphics.net (index 33)
-    PortalGraphicsNet = 33,
-    /// deviantArt (index 34)
-    DeviantArt = 34,
-    /// Pawoo.net (index 35)
-    PawooNet = 35,
-    /// Madokami (index 36)
-    Madokami = 36,
-    /// MangaDex (index 37)
-    MangaDex = 37,
-    /// E-Hentai / H-Misc (index 38)
-    EHentai = 38,
-    /// ArtStation (index 39)
-    ArtStation = 39,
-    /// FurAffinity (index 40)
-    FurAffinity = 40,
-    /// Twitter (index 41)
-    Twitter = 41,
-    /// Furry Network (index 42)
-    FurryNetwork = 42,
-    /// Kemono (index 43)
-    Kemono = 43,
-    /// Skeb (index 44)
-    Skeb = 44,
+pub enum SourceCategory {
+    /// Anime, movies, shows and other video.
+    Video,
+    /// Manga, doujinshi and other print.
+    Manga,
+    /// Imageboards with tagged images, such as Danbooru.
+    Booru,
+    /// Art communities and artist sites, such as Pixiv.
+    Art,
+    /// Social media, such as Twitter.
+    Social,
+    /// Anything else, such as stock photos.
+    Other,
+}
+
+impl SourceCategory {
+    /// Returns the known sources in the category, in order of index.
+    pub fn sources(&self) -> Vec<Source> {
+        Source::all()
+            .iter()
+            .copied()
+            .filter(|x| x.category() == Some(*self))
+            .collect()
+    }
 }
 
 impl Source {
-    /// Returns the name of the source as a string.
-    pub fn name(&self) -> &'static str {
+    /// The largest index a source can be parsed or deserialized with, and stored in a [`DbMask`](crate::DbMask).  SauceNAO's indices are far below this, and capping them keeps a mistyped index from making a mask allocate a huge number of bits.
+    pub const MAX_INDEX: u32 = 1023;
+
+    /// Returns every known source, in order of index.
+    /// ## Example
+    /// ```
+    /// use rustnao::Source;
+    /// let slugs: Vec<&str> = Source::all().iter().map(|x| x.slug()).collect();
+    /// assert!(slugs.contains(&"danbooru"));
+    /// ```
+    pub fn all() -> &'static [Source] {
+        Source::ALL
+    }
+}
+
+/// Converts an index to a Source, keeping indices that are not known as ``Source::Unknown``.
+impl From<u32> for Source {
+    fn from(index: u32) -> Self {
+        Source::from_u32(index).unwrap_or(Source::Unknown(index))
+    }
+}
+
+impl From<Source> for u32 {
+    fn from(source: Source) -> Self {
+        source.index()
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Source::HMagazines => "H-Magazines",
-            Source::HGameCG => "H-Game CG",
-            Source::DoujinshiDB => "DoujinshiDB",
-            Source::Pixiv => "pixiv Images",
-            Source::NicoNicoSeiga => "Nico Nico Seiga",
-            Source::Danbooru => "Danbooru",
-            Source::Drawr => "drawr Images",
-            Source::Nijie => "Nijie Images",
-            Source::Yandere => "Yande.re",
-            Source::Shutterstock => "Shutterstock",
-            Source::Fakku => "FAKKU",
-            Source::HMisc => "H-Misc",
-            Source::TwoDMarket => "2D-Market",
-            Source::MediBang => "MediBang",
-            Source::Anime => "Anime",
-            Source::HAnime => "H-Anime",
-            Source::Movies => "Movies",
-            Source::Shows => "Shows",
-            Source::Gelbooru => "Gelbooru",
-            Source::Konachan => "Konachan",
-            Source::SankakuChannel => "Sankaku Channel",
-            Source::AnimePicturesNet => "Anime-Pictures.net",
-            Source::E621Net => "e621.net",
-            Source::IdolComplex => "Idol Complex",
-            Source::BcyNetIllust => "bcy.net Illust",
-            Source::BcyNetCosplay => "bcy.net Cosplay",
-            Source::PortalGraphicsNet => "PortalGraphics.net",
-            Source::DeviantArt => "deviantArt",
-            Source::PawooNet => "Pawoo.net",
-            Source::Madokami => "Madokami",
-            Source::MangaDex => "MangaDex",
-            Source::EHentai => "E-Hentai",
-            Source::ArtStation => "ArtStation",
-            Source::FurAffinity => "FurAffinity",
-            Source::Twitter => "Twitter",
-            Source::FurryNetwork => "Furry Network",
-            Source::Kemono => "Kemono",
-            Source::Skeb => "Skeb",
+            Source::Unknown(index) => write!(f, "Unknown (index {})", index),
+            _ => f.write_str(self.name()),
+        }
+    }
+}
+
+/// Parses a source from its slug, its name, or its index.  Slugs and names are matched case-insensitively, and indices that are not known are parsed as ``Source::Unknown``, as long as they are not above [`Source::MAX_INDEX`].
+impl FromStr for Source {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(index) = s.parse::<u64>() {
+            return match u32::try_from(index) {
+                Ok(index) if index <= Source::MAX_INDEX => Ok(Source::from(index)),
+                _ => Err(Error::InvalidParameters(format!(
+                    "source index {} is above the maximum of {}",
+                    index,
+                    Source::MAX_INDEX
+                ))),
+            };
         }
+
+        Source::all()
+            .iter()
+            .find(|x| x.slug().eq_ignore_ascii_case(s) || x.name().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| Error::InvalidParameters(format!("unknown source \"{}\"", s)))
     }
+}
 
-    /// Converts a u32 index to a Source enum.
-    pub fn from_u32(index: u32) -> Option<Self> {
-        match index {
-            0 => Some(Source::HMagazines),
-            2 => Some(Source::HGameCG),
-            3 => Some(Source::DoujinshiDB),
-            5 => Some(Source::Pixiv),
-            8 => Some(Source::NicoNicoSeiga),
-            9 => Some(Source::Danbooru),
-            10 => Some(Source::Drawr),
-            11 => Some(Source::Nijie),
-            12 => Some(Source::Yandere),
-            15 => Some(Source::Shutterstock),
-            16 => Some(Source::Fakku),
-            18 => Some(Source::HMisc),
-            19 => Some(Source::TwoDMarket),
-            20 => Some(Source::MediBang),
-            21 => Some(Source::Anime),
-            22 => Some(Source::HAnime),
-            23 => Some(Source::Movies),
-            24 => Some(Source::Shows),
-            25 => Some(Source::Gelbooru),
-            26 => Some(Source::Konachan),
-            27 => Some(Source::SankakuChannel),
-            28 => Some(Source::AnimePicturesNet),
-            29 => Some(Source::E621Net),
-            30 => Some(Source::IdolComplex),
-            31 => Some(Source::BcyNetIllust),
-            32 => Some(Source::BcyNetCosplay),
-            33 => Some(Source::PortalGraphicsNet),
-            34 => Some(Source::DeviantArt),
-            35 => Some(Source::PawooNet),
-            36 => Some(Source::Madokami),
-            37 => Some(Source::MangaDex),
-            38 => Some(Source::EHentai),
-            39 => Some(Source::ArtStation),
-            40 => Some(Source::FurAffinity),
-            41 => Some(Source::Twitter),
-            42 => Some(Source::FurryNetwork),
-            43 => Some(Source::Kemono),
-            44 => Some(Source::Skeb),
-            _ => None,
+impl Serialize for Source {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            _ if !serializer.is_human_readable() => serializer.serialize_u32(self.index()),
+            Source::Unknown(index) => serializer.serialize_u32(*index),
+            _ => serializer.serialize_str(self.slug()),
         }
     }
-}
\ No newline at end of file
+}
+
+impl<'de> Deserialize<'de> for Source {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SourceVisitor;
+
+        impl Visitor<'_> for SourceVisitor {
+            type Value = Source;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a SauceNAO source slug, name or index")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Source, E> {
+                v.parse()
+                    .map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Source, E> {
+                u32::try_from(v)
+                    .ok()
+                    .filter(|x| *x <= Source::MAX_INDEX)
+                    .map(Source::from)
+                    .ok_or_else(|| E::invalid_value(Unexpected::Unsigned(v), &self))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Source, E> {
+                u64::try_from(v)
+                    .map_err(|_| E::invalid_value(Unexpected::Signed(v), &self))
+                    .and_then(|v| self.visit_u64(v))
+            }
+        }
+
+        // Compact formats are not self-describing, so only the index is written to them.
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(SourceVisitor)
+        } else {
+            deserializer.deserialize_u32(SourceVisitor)
+        }
+    }
+}
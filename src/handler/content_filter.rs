@@ -0,0 +1,29 @@
+//! The ContentFilter enum, for SauceNAO to hide explicit results on its end.
+
+/// How SauceNAO should filter out explicit results before returning them, sent as its ``hide`` parameter.
+///
+/// SauceNAO rates results itself, so this works for any source, unlike excluding sources with ``DbMask::adult``.
+/// ## Example
+/// ```
+/// use rustnao::{ContentFilter, HandlerBuilder};
+/// let handle = HandlerBuilder::default().api_key("your_api_key").content_filter(ContentFilter::SafeOnly).build();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ContentFilter {
+    /// Show all results.
+    #[default]
+    ShowAll = 0,
+    /// Hide results that are expected to be explicit.
+    HideExpected = 1,
+    /// Hide results that are expected or suspected to be explicit.
+    HideSuspected = 2,
+    /// Hide all results besides those that are expected to be safe.
+    SafeOnly = 3,
+}
+
+impl ContentFilter {
+    /// Returns the value sent to SauceNAO for the filter.
+    pub fn value(&self) -> u32 {
+        *self as u32
+    }
+}
@@ -0,0 +1,264 @@
+//! The DbMask struct, a set of sources encoded as the bitmask SauceNAO takes for ``dbmask`` and ``dbmaski``.
+
+use super::constants::{Source, SourceCategory};
+use super::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// The largest power of 10 that fits in a u64, used to convert masks to and from decimal in chunks.
+const DECIMAL_CHUNK: u64 = 10_000_000_000_000_000_000;
+const DECIMAL_CHUNK_DIGITS: usize = 19;
+/// The number of words needed to store every index up to ``Source::MAX_INDEX``.
+const MAX_WORDS: usize = Source::MAX_INDEX as usize / 64 + 1;
+
+/// A set of sources, sent to SauceNAO as a bitmask with one bit per index.  Any index up to [`Source::MAX_INDEX`] can be stored, so masks are encoded correctly no matter how many indices SauceNAO has.
+/// ## Example
+/// ```
+/// use rustnao::{DbMask, HandlerBuilder, Source};
+/// let mask: DbMask = [Source::Pixiv, Source::Danbooru].into_iter().collect();
+/// assert_eq!(mask.to_string(), "544");
+/// assert_eq!("544".parse::<DbMask>().unwrap(), mask);
+///
+/// let boorus = DbMask::from(vec![Source::Danbooru, Source::Gelbooru]);
+/// assert_eq!(mask.intersection(&boorus), DbMask::from(Source::Danbooru));
+///
+/// let handle = HandlerBuilder::default().api_key("your_api_key").db_mask(mask.union(&boorus)).build();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct DbMask {
+    /// The bits of the mask, least significant word first, without any trailing zero words.
+    words: Vec<u64>,
+}
+
+impl DbMask {
+    /// Creates an empty mask.
+    pub fn new() -> DbMask {
+        DbMask::default()
+    }
+
+    /// Creates a mask of every known source.
+    pub fn all() -> DbMask {
+        Source::all().iter().copied().collect()
+    }
+
+    /// Creates a mask of every known source that only or predominantly has adult content.
+    pub fn adult() -> DbMask {
+        Source::all()
+            .iter()
+            .copied()
+            .filter(Source::is_adult)
+            .collect()
+    }
+
+    /// Adds a source to the mask.  Sources with an index above [`Source::MAX_INDEX`] are ignored.
+    pub fn insert(&mut self, source: Source) {
+        if source.index() > Source::MAX_INDEX {
+            return;
+        }
+        let (word, bit) = Self::position(source);
+        if self.words.len() <= word {
+            self.words.resize(word + 1, 0);
+        }
+        self.words[word] |= bit;
+    }
+
+    /// Removes a source from the mask.
+    pub fn remove(&mut self, source: Source) {
+        let (word, bit) = Self::position(source);
+        if let Some(x) = self.words.get_mut(word) {
+            *x &= !bit;
+        }
+        self.trim();
+    }
+
+    /// Returns whether the mask contains a source.
+    pub fn contains(&self, source: Source) -> bool {
+        let (word, bit) = Self::position(source);
+        self.words.get(word).is_some_and(|x| x & bit != 0)
+    }
+
+    /// Returns whether the mask has no sources.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Returns the number of sources in the mask.
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|x| x.count_ones() as usize).sum()
+    }
+
+    /// Returns the sources in the mask, in order of index.  Indices that are not known are returned as ``Source::Unknown``.
+    pub fn iter(&self) -> impl Iterator<Item = Source> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, word)| {
+            (0..64)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| Source::from(i as u32 * 64 + bit))
+        })
+    }
+
+    /// Returns a mask of the sources in either this mask or the other one.
+    pub fn union(&self, other: &DbMask) -> DbMask {
+        let (long, short) = if self.words.len() >= other.words.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut result = long.clone();
+        for (x, y) in result.words.iter_mut().zip(&short.words) {
+            *x |= y;
+        }
+        result
+    }
+
+    /// Returns a mask of the sources in both this mask and the other one.
+    pub fn intersection(&self, other: &DbMask) -> DbMask {
+        let mut result = DbMask {
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(x, y)| x & y)
+                .collect(),
+        };
+        result.trim();
+        result
+    }
+
+    /// Returns a mask of the sources in this mask that are not in the other one.
+    pub fn difference(&self, other: &DbMask) -> DbMask {
+        let mut result = self.clone();
+        for (x, y) in result.words.iter_mut().zip(&other.words) {
+            *x &= !y;
+        }
+        result.trim();
+        result
+    }
+
+    /// Returns a mask of every known source that is not in this mask.
+    pub fn complement(&self) -> DbMask {
+        DbMask::all().difference(self)
+    }
+
+    /// Returns which word, and which bit of it, a source is stored in.
+    fn position(source: Source) -> (usize, u64) {
+        let index = source.index();
+        ((index / 64) as usize, 1 << (index % 64))
+    }
+
+    /// Removes trailing zero words, so equal masks are stored the same way.
+    fn trim(&mut self) {
+        while self.words.last() == Some(&0) {
+            self.words.pop();
+        }
+    }
+}
+
+impl FromIterator<Source> for DbMask {
+    fn from_iter<I: IntoIterator<Item = Source>>(iter: I) -> Self {
+        let mut mask = DbMask::new();
+        mask.extend(iter);
+        mask
+    }
+}
+
+impl Extend<Source> for DbMask {
+    fn extend<I: IntoIterator<Item = Source>>(&mut self, iter: I) {
+        for source in iter {
+            self.insert(source);
+        }
+    }
+}
+
+impl From<Source> for DbMask {
+    fn from(source: Source) -> Self {
+        DbMask::from_iter([source])
+    }
+}
+
+impl From<SourceCategory> for DbMask {
+    fn from(category: SourceCategory) -> Self {
+        category.sources().into_iter().collect()
+    }
+}
+
+impl From<Vec<Source>> for DbMask {
+    fn from(sources: Vec<Source>) -> Self {
+        sources.into_iter().collect()
+    }
+}
+
+impl From<&[Source]> for DbMask {
+    fn from(sources: &[Source]) -> Self {
+        sources.iter().copied().collect()
+    }
+}
+
+/// Formats the mask as the decimal number SauceNAO takes.
+impl fmt::Display for DbMask {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Repeatedly divide by the largest power of 10 that fits in a word, collecting the
+        // remainders as the digits from least to most significant.
+        let mut words = self.words.clone();
+        let mut chunks = Vec::new();
+        while !words.is_empty() {
+            let mut remainder: u128 = 0;
+            for word in words.iter_mut().rev() {
+                let value = (remainder << 64) | *word as u128;
+                *word = (value / DECIMAL_CHUNK as u128) as u64;
+                remainder = value % DECIMAL_CHUNK as u128;
+            }
+            chunks.push(remainder as u64);
+            while words.last() == Some(&0) {
+                words.pop();
+            }
+        }
+
+        match chunks.split_last() {
+            None => write!(f, "0"),
+            Some((first, rest)) => {
+                write!(f, "{}", first)?;
+                for chunk in rest.iter().rev() {
+                    write!(f, "{:0width$}", chunk, width = DECIMAL_CHUNK_DIGITS)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Parses a mask from the decimal number SauceNAO takes, returning an error if it has a bit above [`Source::MAX_INDEX`].
+impl FromStr for DbMask {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() || !s.bytes().all(|x| x.is_ascii_digit()) {
+            return Err(Error::InvalidParameters(format!(
+                "\"{}\" is not a valid database mask",
+                s
+            )));
+        }
+
+        let mut mask = DbMask::new();
+        for digit in s.bytes().map(|x| (x - b'0') as u128) {
+            // Multiply the mask by 10 and add the digit, carrying between words.
+            let mut carry = digit;
+            for word in mask.words.iter_mut() {
+                let value = *word as u128 * 10 + carry;
+                *word = value as u64;
+                carry = value >> 64;
+            }
+            if carry > 0 {
+                mask.words.push(carry as u64);
+            }
+            if mask.words.len() > MAX_WORDS {
+                return Err(Error::InvalidParameters(format!(
+                    "database mask has a source index above the maximum of {}",
+                    Source::MAX_INDEX
+                )));
+            }
+        }
+        mask.trim();
+        Ok(mask)
+    }
+}
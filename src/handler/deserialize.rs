@@ -1,24 +1,97 @@
 //! Collection of structs used to deserialize the API JSON results, upon which it is further processed.
 
-use serde::Deserialize;
+use super::response::IndexStatus;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
 use std::collections::HashMap;
+use std::str::FromStr;
+
+/// A value SauceNAO sends as either a string or a number, depending on the index.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StringOrNumber {
+    String(String),
+    Number(serde_json::Number),
+}
+
+/// Deserializes an optional string that may be sent as a number.
+pub(in crate::handler) fn opt_string<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    Ok(
+        Option::<StringOrNumber>::deserialize(deserializer)?.map(|x| match x {
+            StringOrNumber::String(x) => x,
+            StringOrNumber::Number(x) => x.to_string(),
+        }),
+    )
+}
+
+/// Deserializes an optional number that may be sent as a string.  Strings that are empty or not
+/// numbers, and numbers that do not fit, are treated as missing.
+pub(in crate::handler) fn opt_number<'de, D: Deserializer<'de>, T: FromStr>(
+    deserializer: D,
+) -> Result<Option<T>, D::Error> {
+    Ok(
+        Option::<StringOrNumber>::deserialize(deserializer)?.and_then(|x| match x {
+            StringOrNumber::String(x) => x.trim().parse().ok(),
+            StringOrNumber::Number(x) => x.to_string().parse().ok(),
+        }),
+    )
+}
+
+/// Deserializes a number that may be sent as a string, treating it as the default if it is
+/// missing or not a number.
+pub(in crate::handler) fn number_or_default<'de, D: Deserializer<'de>, T: FromStr + Default>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    Ok(opt_number(deserializer)?.unwrap_or_default())
+}
+
+/// Deserializes an optional list of strings that may be sent as a single string.  Anything else
+/// is treated as missing, and items that are not strings or numbers are dropped.
+pub(in crate::handler) fn opt_string_list<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<String>>, D::Error> {
+    let to_string = |x: Value| match x {
+        Value::String(x) => Some(x),
+        Value::Number(x) => Some(x.to_string()),
+        _ => None,
+    };
+
+    Ok(match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::Array(x)) => Some(x.into_iter().filter_map(to_string).collect()),
+        Some(x) => to_string(x).map(|x| vec![x]),
+        None => None,
+    })
+}
 
 #[derive(Deserialize, Debug, Default, Clone)]
+#[serde(default)]
 pub struct Header {
-    pub similarity: String,
-    pub thumbnail: String,
-    pub index_id: u32,
-    pub index_name: String,
+    #[serde(deserialize_with = "opt_number")]
+    pub similarity: Option<f64>,
+    #[serde(deserialize_with = "opt_string")]
+    pub thumbnail: Option<String>,
+    #[serde(deserialize_with = "opt_number")]
+    pub index_id: Option<u32>,
+    #[serde(deserialize_with = "opt_string")]
+    pub index_name: Option<String>,
 }
 
 #[derive(Deserialize, Debug, Default, Clone)]
+#[serde(default)]
 pub struct Data {
-    #[serde(default)]
-    pub ext_urls: Vec<String>,
+    #[serde(deserialize_with = "opt_string_list")]
+    pub ext_urls: Option<Vec<String>>,
+    #[serde(deserialize_with = "opt_string")]
     pub title: Option<String>,
+    #[serde(deserialize_with = "opt_string")]
     pub source: Option<String>,
+    #[serde(deserialize_with = "opt_string_list")]
     pub creator: Option<Vec<String>>,
+    #[serde(deserialize_with = "opt_string")]
     pub eng_name: Option<String>,
+    #[serde(deserialize_with = "opt_string")]
     pub jp_name: Option<String>,
 
     #[serde(flatten, skip_serializing_if = "HashMap::is_empty")]
@@ -35,23 +108,42 @@ pub struct SauceJSON {
 
 #[derive(Deserialize, Debug)]
 pub struct ResultHeader {
-    #[serde(default)]
-    pub long_limit: String,
-    #[serde(default)]
-    pub short_limit: String,
-    #[serde(default)]
-    pub long_remaining: u32,
-    #[serde(default)]
-    pub short_remaining: u32,
-    #[serde(default)]
-    pub message: String,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "opt_number")]
+    pub long_limit: Option<u32>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub short_limit: Option<u32>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub long_remaining: Option<u32>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub short_remaining: Option<u32>,
+    #[serde(default, deserialize_with = "opt_string")]
+    pub message: Option<String>,
+    #[serde(default, deserialize_with = "number_or_default")]
     pub status: i32,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub user_id: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub account_type: Option<u32>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub results_requested: Option<u32>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub results_returned: Option<u32>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub search_depth: Option<u64>,
+    #[serde(default, deserialize_with = "opt_number")]
+    pub minimum_similarity: Option<f64>,
+    #[serde(default, deserialize_with = "opt_string")]
+    pub query_image: Option<String>,
+    #[serde(default, deserialize_with = "opt_string")]
+    pub query_image_display: Option<String>,
+    #[serde(default)]
+    pub index: HashMap<String, IndexStatus>,
 }
 
+/// The results are kept as raw JSON, so that one that cannot be read does not fail the others.
 #[derive(Deserialize, Debug)]
 pub struct SauceResult {
     pub header: ResultHeader,
     #[serde(default)]
-    pub results: Option<Vec<SauceJSON>>,
-}
\ No newline at end of file
+    pub results: Option<Vec<Value>>,
+}
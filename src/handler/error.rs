@@ -2,26 +2,37 @@
 //! Much thanks to Andrew Gallant for the basis of this part of the library... followed [the following
 //! code for this](https://github.com/BurntSushi/imdb-rename/blob/master/imdb-index/src/error.rs).
 
-use std::result;
+use super::constants::MAX_BODY_SNIPPET;
+use super::transport::{BoxError, TransportResponse};
+use reqwest::header::{CONTENT_TYPE, RETRY_AFTER};
+use reqwest::StatusCode;
+use std::error::Error as StdError;
+use std::time::Duration;
+use std::{io, result};
 use thiserror::Error;
 
 /// A type alias for handling errors related to rustnao.
 pub type Result<T> = result::Result<T, Error>;
 
 /// An error that can occur while interacting to the SauceNAO API.
+///
+/// More variants may be added in future releases, so matching on it needs a wildcard arm.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum Error {
     /// An error when forming the URL for the API.
     #[error("ERROR: URL was invalid, error was due to: {0}")]
-    InvalidParse(String),
+    InvalidParse(#[source] BoxError),
     /// An error when getting the file path of a file for the API.
     #[error("ERROR: File path was invalid, error was due to: {0}")]
-    InvalidFile(String),
+    InvalidFile(#[source] io::Error),
     /// An error when trying to deserialize the resulting JSON from the API.
     #[error("ERROR: Could not properly serde results: {0}")]
-    InvalidSerde(String),
+    InvalidSerde(#[source] serde_json::Error),
     /// An error when receiving an unsuccessful code from the SauceNAO API.
-    #[error("ERROR: Recieved an invalid status code {code} after API call with message: \"{message}\"")]
+    #[error(
+        "ERROR: Recieved an invalid status code {code} after API call with message: \"{message}\""
+    )]
     InvalidCode {
         /// The error code from SauceNAO.
         code: i32,
@@ -30,44 +41,272 @@ pub enum Error {
     },
     /// An error when trying to send an invalid request to the API.
     #[error("ERROR: Failed to make the request, error was due to: {0}")]
-    InvalidRequest(String),
+    InvalidRequest(#[source] BoxError),
+    /// An error when the short (30 second) search limit has been used up.
+    #[error("ERROR: The short search limit has been reached: {0}")]
+    ShortLimitReached(String),
+    /// An error when the long (daily) search limit has been used up.
+    #[error("ERROR: The daily search limit has been reached: {0}")]
+    LongLimitReached(String),
+    /// An error when SauceNAO rejects the API key.
+    #[error("ERROR: The API key was rejected with status code {code} and message: \"{message}\"")]
+    Unauthorized {
+        /// The error code from SauceNAO.
+        code: i32,
+        /// The message showing the cause of the error from SauceNAO.
+        message: String,
+    },
+    /// An error when SauceNAO could not process the image, such as it not being a valid image.
+    #[error("ERROR: The image was rejected with status code {code} and message: \"{message}\"")]
+    InvalidImage {
+        /// The error code from SauceNAO.
+        code: i32,
+        /// The message showing the cause of the error from SauceNAO.
+        message: String,
+    },
+    /// An error when the uploaded image is larger than SauceNAO accepts.
+    #[error("ERROR: The image was too large, rejected with status code {code} and message: \"{message}\"")]
+    FileTooLarge {
+        /// The error code from SauceNAO.
+        code: i32,
+        /// The message showing the cause of the error from SauceNAO.
+        message: String,
+    },
+    /// An error when the search failed on SauceNAO's end, such as it being overloaded.
+    #[error(
+        "ERROR: The search failed on the server with status code {code} and message: \"{message}\""
+    )]
+    ServerError {
+        /// The error code from SauceNAO.
+        code: i32,
+        /// The message showing the cause of the error from SauceNAO.
+        message: String,
+    },
+    /// An error when the API responds with HTTP 429 Too Many Requests.
+    #[error("ERROR: Too many requests were made, the response was: \"{body}\"")]
+    TooManyRequests {
+        /// How long the API asked to wait before retrying, if it said.
+        retry_after: Option<Duration>,
+        /// The start of the body of the response.
+        body: String,
+    },
+    /// An error when the API responds with HTTP 401 Unauthorized or 403 Forbidden.
+    #[error("ERROR: Access was denied with HTTP status {status}, the response was: \"{body}\"")]
+    Forbidden {
+        /// The HTTP status code of the response.
+        status: StatusCode,
+        /// The start of the body of the response.
+        body: String,
+    },
+    /// An error when the API responds with an HTTP 5xx server error.
+    #[error("ERROR: The server failed with HTTP status {status}, the response was: \"{body}\"")]
+    HttpServerError {
+        /// The HTTP status code of the response.
+        status: StatusCode,
+        /// The start of the body of the response.
+        body: String,
+    },
+    /// An error when the API responds with any other HTTP error, or a successful response that is not JSON.
+    #[error("ERROR: Received an unexpected response with HTTP status {status}, the response was: \"{body}\"")]
+    UnexpectedResponse {
+        /// The HTTP status code of the response.
+        status: StatusCode,
+        /// The content type of the response, if it had one.
+        content_type: Option<String>,
+        /// The start of the body of the response.
+        body: String,
+    },
     /// An error with some data that is passed in by the user.
     #[error("ERROR: An invalid parameter was passed, error was due to: {0}")]
     InvalidParameters(String),
 }
 
+/// Phrases SauceNAO uses when the daily search limit is reached.
+const LONG_LIMIT_PHRASES: &[&str] = &["daily search limit", "searches every 24 hours"];
+/// Phrases SauceNAO uses when the 30 second search limit is reached.
+const SHORT_LIMIT_PHRASES: &[&str] = &["search rate too high", "searches every 30 seconds"];
+/// Phrases SauceNAO uses when the API key is missing or invalid.
+const API_KEY_PHRASES: &[&str] = &["api key", "api_key"];
+/// Phrases SauceNAO uses when the uploaded file is too large.
+const FILE_TOO_LARGE_PHRASES: &[&str] = &["file is too large", "file size"];
+/// Phrases SauceNAO uses when the image could not be searched.
+const INVALID_IMAGE_PHRASES: &[&str] = &[
+    "image could not be processed",
+    "not a valid image",
+    "image dimensions",
+    "problem with the image",
+];
+
+impl Error {
+    /// Creates an error from an unsuccessful status code and message returned by SauceNAO.
+    /// Positive codes are failures on SauceNAO's end, while negative codes are problems with the
+    /// search itself.  The code is checked first, and only the exact phrases SauceNAO uses are
+    /// matched in the message, so unrelated messages are never taken as retryable.
+    pub(crate) fn from_status(code: i32, message: String) -> Error {
+        let lower = message.to_lowercase();
+        let has = |phrases: &[&str]| phrases.iter().any(|x| lower.contains(x));
+        if code > 0 {
+            Error::ServerError { code, message }
+        } else if has(LONG_LIMIT_PHRASES) {
+            Error::LongLimitReached(message)
+        } else if code == -2 || has(SHORT_LIMIT_PHRASES) {
+            Error::ShortLimitReached(message)
+        } else if has(API_KEY_PHRASES) {
+            Error::Unauthorized { code, message }
+        } else if has(FILE_TOO_LARGE_PHRASES) {
+            Error::FileTooLarge { code, message }
+        } else if code == -3 || code == -4 || has(INVALID_IMAGE_PHRASES) {
+            Error::InvalidImage { code, message }
+        } else {
+            Error::InvalidCode { code, message }
+        }
+    }
+
+    /// Creates an error from a response that was an HTTP error, or was not JSON.
+    pub(crate) fn from_response(response: &TransportResponse) -> Error {
+        let text = String::from_utf8_lossy(&response.body);
+        let body = match text.char_indices().nth(MAX_BODY_SNIPPET) {
+            Some((end, _)) => format!("{}...", &text[..end]),
+            None => text.into_owned(),
+        };
+        let status = response.status;
+
+        match status {
+            StatusCode::TOO_MANY_REQUESTS => Error::TooManyRequests {
+                retry_after: response
+                    .headers
+                    .get(RETRY_AFTER)
+                    .and_then(|x| x.to_str().ok())
+                    .and_then(|x| x.trim().parse().ok())
+                    .map(Duration::from_secs),
+                body,
+            },
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Error::Forbidden { status, body },
+            _ if status.is_server_error() => Error::HttpServerError { status, body },
+            _ => Error::UnexpectedResponse {
+                status,
+                content_type: response
+                    .headers
+                    .get(CONTENT_TYPE)
+                    .and_then(|x| x.to_str().ok())
+                    .map(str::to_string),
+                body,
+            },
+        }
+    }
+
+    /// Finds the first error of a given type in the chain of sources of this error.
+    fn find_source<T: StdError + 'static>(&self) -> Option<&T> {
+        let mut current = StdError::source(self);
+        while let Some(err) = current {
+            if let Some(found) = err.downcast_ref::<T>() {
+                return Some(found);
+            }
+            current = err.source();
+        }
+        None
+    }
+
+    /// Returns the HTTP status code of the response that caused the error, if there was one.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Error::TooManyRequests { .. } => Some(StatusCode::TOO_MANY_REQUESTS),
+            Error::Forbidden { status, .. }
+            | Error::HttpServerError { status, .. }
+            | Error::UnexpectedResponse { status, .. } => Some(*status),
+            _ => self
+                .find_source::<reqwest::Error>()
+                .and_then(reqwest::Error::status),
+        }
+    }
+
+    /// Returns how long the API asked to wait before retrying, if it said.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Error::TooManyRequests { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// Returns whether the error was caused by the request timing out.
+    pub fn is_timeout(&self) -> bool {
+        self.find_source::<reqwest::Error>()
+            .is_some_and(reqwest::Error::is_timeout)
+            || self.io_kind() == Some(io::ErrorKind::TimedOut)
+    }
+
+    /// Returns whether the error was caused by failing to connect to the API.
+    pub fn is_connect(&self) -> bool {
+        self.find_source::<reqwest::Error>()
+            .is_some_and(reqwest::Error::is_connect)
+    }
+
+    /// Returns the kind of the IO error that caused the error, such as a file not being found, if there was one.
+    /// ## Example
+    /// ```
+    /// use rustnao::HandlerBuilder;
+    /// use std::io::ErrorKind;
+    /// # tokio_test::block_on(async {
+    /// let handle = HandlerBuilder::default().build();
+    /// let err = handle.get_sauce("./fake_file.png", None, None).await.unwrap_err();
+    /// assert_eq!(err.io_kind(), Some(ErrorKind::NotFound));
+    /// # });
+    /// ```
+    pub fn io_kind(&self) -> Option<io::ErrorKind> {
+        self.find_source::<io::Error>().map(io::Error::kind)
+    }
+
+    /// Returns whether the error is likely transient, such that retrying the same search later may succeed.
+    /// ## Example
+    /// ```
+    /// use rustnao::Error;
+    /// assert!(Error::ShortLimitReached("Search Rate Too High.".to_string()).is_retryable());
+    /// assert!(!Error::LongLimitReached("Daily Search Limit Exceeded.".to_string()).is_retryable());
+    /// ```
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Error::InvalidRequest(_)
+                | Error::ShortLimitReached(_)
+                | Error::ServerError { .. }
+                | Error::TooManyRequests { .. }
+                | Error::HttpServerError { .. }
+        )
+    }
+}
+
 impl From<serde_json::Error> for Error {
     fn from(err: serde_json::Error) -> Self {
-        Error::InvalidSerde(err.to_string())
+        Error::InvalidSerde(err)
     }
 }
 
 impl From<url::ParseError> for Error {
     fn from(err: url::ParseError) -> Self {
-        Error::InvalidParse(err.to_string())
+        Error::InvalidParse(Box::new(err))
     }
 }
 
 impl From<std::num::ParseIntError> for Error {
     fn from(err: std::num::ParseIntError) -> Self {
-        Error::InvalidParse(err.to_string())
+        Error::InvalidParse(Box::new(err))
     }
 }
 
 impl From<std::num::ParseFloatError> for Error {
     fn from(err: std::num::ParseFloatError) -> Self {
-        Error::InvalidParse(err.to_string())
+        Error::InvalidParse(Box::new(err))
     }
 }
 
-impl From<std::io::Error> for Error {
-    fn from(err: std::io::Error) -> Self {
-        Error::InvalidFile(err.to_string())
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::InvalidFile(err)
     }
 }
 
 impl From<reqwest::Error> for Error {
     fn from(err: reqwest::Error) -> Self {
-        Error::InvalidRequest(err.to_string())
+        Error::InvalidRequest(Box::new(err))
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,129 @@
+//! The SearchInput enum, representing an image to be searched for by the Handler.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncRead;
+use url::Url;
+
+/// The file name used for uploads when one cannot be determined.
+pub(in crate::handler) const DEFAULT_FILE_NAME: &str = "image.jpg";
+
+/// An image to search for, stating explicitly whether it is a URL, a local file, or in-memory data.
+/// ## Example
+/// ```
+/// use rustnao::SearchInput;
+/// use std::path::Path;
+///
+/// // Strings are parsed as URLs when they have a scheme, and treated as file paths otherwise.
+/// assert!(matches!(SearchInput::from("https://i.imgur.com/W42kkKS.jpg"), SearchInput::Url(_)));
+/// assert!(matches!(SearchInput::from("http_cache.png"), SearchInput::Path(_)));
+///
+/// // Or the intent can be stated directly.
+/// let path = SearchInput::from(Path::new("./tests/test.jpg"));
+/// let bytes = SearchInput::bytes(vec![0xFF, 0xD8, 0xFF], "image.jpg");
+/// ```
+pub enum SearchInput {
+    /// An image URL for SauceNAO to fetch itself.  ``file://`` URLs are read and uploaded as local files, and any other scheme besides ``http`` and ``https`` is rejected.
+    Url(Url),
+    /// A path to a local image file to upload.
+    Path(PathBuf),
+    /// An image already in memory to upload.
+    Bytes {
+        /// The contents of the image.
+        data: Vec<u8>,
+        /// The file name to upload the image with.
+        file_name: String,
+    },
+    /// A reader to read the image to upload from.
+    Reader {
+        /// The reader to read the image from.
+        reader: Box<dyn AsyncRead + Send + Unpin>,
+        /// The file name to upload the image with.
+        file_name: String,
+    },
+}
+
+impl SearchInput {
+    /// Creates a SearchInput from an image that is already in memory.
+    pub fn bytes(data: impl Into<Vec<u8>>, file_name: &str) -> SearchInput {
+        SearchInput::Bytes {
+            data: data.into(),
+            file_name: file_name.to_string(),
+        }
+    }
+
+    /// Creates a SearchInput from a reader that the image will be read from.
+    pub fn reader<R: AsyncRead + Send + Unpin + 'static>(
+        reader: R, file_name: &str,
+    ) -> SearchInput {
+        SearchInput::Reader {
+            reader: Box::new(reader),
+            file_name: file_name.to_string(),
+        }
+    }
+}
+
+impl fmt::Debug for SearchInput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SearchInput::Url(url) => f.debug_tuple("Url").field(&url.as_str()).finish(),
+            SearchInput::Path(path) => f.debug_tuple("Path").field(path).finish(),
+            SearchInput::Bytes { data, file_name } => f
+                .debug_struct("Bytes")
+                .field("len", &data.len())
+                .field("file_name", file_name)
+                .finish(),
+            SearchInput::Reader { file_name, .. } => f
+                .debug_struct("Reader")
+                .field("file_name", file_name)
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+impl From<Url> for SearchInput {
+    fn from(url: Url) -> Self {
+        SearchInput::Url(url)
+    }
+}
+
+impl From<PathBuf> for SearchInput {
+    fn from(path: PathBuf) -> Self {
+        SearchInput::Path(path)
+    }
+}
+
+impl From<&Path> for SearchInput {
+    fn from(path: &Path) -> Self {
+        SearchInput::Path(path.to_path_buf())
+    }
+}
+
+impl From<Vec<u8>> for SearchInput {
+    fn from(data: Vec<u8>) -> Self {
+        SearchInput::bytes(data, DEFAULT_FILE_NAME)
+    }
+}
+
+/// Strings that parse as a URL with a scheme are treated as URLs, and anything else as a file path.
+/// Single letter schemes are treated as Windows drive letters, and so as file paths.
+impl From<&str> for SearchInput {
+    fn from(image: &str) -> Self {
+        match Url::parse(image) {
+            Ok(url) if url.scheme().len() > 1 => SearchInput::Url(url),
+            _ => SearchInput::Path(PathBuf::from(image)),
+        }
+    }
+}
+
+impl From<String> for SearchInput {
+    fn from(image: String) -> Self {
+        SearchInput::from(image.as_str())
+    }
+}
+
+impl From<&String> for SearchInput {
+    fn from(image: &String) -> Self {
+        SearchInput::from(image.as_str())
+    }
+}
@@ -0,0 +1,95 @@
+//! The SearchOptions struct, overriding the defaults of a Handler for a single search.
+
+use super::content_filter::ContentFilter;
+use super::db_mask::DbMask;
+use super::threshold::SimilarityThreshold;
+
+/// Options for a single search, each overriding the default set on the Handler.  Any option that is not set uses the Handler's default.
+/// ## Example
+/// ```no_run
+/// use rustnao::{ContentFilter, HandlerBuilder, SearchOptions, SourceCategory};
+/// # tokio_test::block_on(async {
+/// let handle = HandlerBuilder::default().api_key("your_api_key").build();
+/// let response = handle
+///     .search(
+///         "https://i.imgur.com/W42kkKS.jpg",
+///         SearchOptions::default()
+///             .db_mask(SourceCategory::Booru)
+///             .num_results(5)
+///             .min_similarity(60)
+///             .content_filter(ContentFilter::SafeOnly),
+///     )
+///     .await
+///     .unwrap();
+/// # });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    pub(in crate::handler) db: Option<u32>,
+    pub(in crate::handler) db_mask: Option<DbMask>,
+    pub(in crate::handler) db_mask_i: Option<DbMask>,
+    pub(in crate::handler) testmode: Option<bool>,
+    pub(in crate::handler) num_results: Option<u32>,
+    pub(in crate::handler) min_similarity: Option<f64>,
+    pub(in crate::handler) similarity_threshold: Option<SimilarityThreshold>,
+    pub(in crate::handler) empty_filter_enabled: Option<bool>,
+    pub(in crate::handler) content_filter: Option<ContentFilter>,
+}
+
+impl SearchOptions {
+    /// Sets a database index to be searched.
+    pub fn db(&mut self, db: u32) -> &mut SearchOptions {
+        self.db = Some(db);
+        self
+    }
+
+    /// Sets which database indices you want included on search.  This takes a [`DbMask`], or anything that converts into one, such as a ``Vec<Source>``.
+    pub fn db_mask<M: Into<DbMask>>(&mut self, db_mask: M) -> &mut SearchOptions {
+        self.db_mask = Some(db_mask.into());
+        self
+    }
+
+    /// Sets which database indices you want excluded on search.  This takes a [`DbMask`], or anything that converts into one, such as a ``Vec<Source>``.
+    pub fn db_mask_i<M: Into<DbMask>>(&mut self, db_mask_i: M) -> &mut SearchOptions {
+        self.db_mask_i = Some(db_mask_i.into());
+        self
+    }
+
+    /// Sets whether testmode should be enabled, returning only one result per index.
+    pub fn testmode(&mut self, testmode: bool) -> &mut SearchOptions {
+        self.testmode = Some(testmode);
+        self
+    }
+
+    /// Sets the maximum number of results you want returned, between 1 and 999.
+    pub fn num_results(&mut self, num_results: u32) -> &mut SearchOptions {
+        self.num_results = Some(num_results);
+        self
+    }
+
+    /// Sets the minimum similarity for results, between 0 and 100.
+    pub fn min_similarity<T: Into<f64>>(&mut self, min_similarity: T) -> &mut SearchOptions {
+        self.min_similarity = Some(min_similarity.into());
+        self
+    }
+
+    /// Sets how the minimum similarity that results are filtered with is decided.
+    pub fn similarity_threshold(
+        &mut self, similarity_threshold: SimilarityThreshold,
+    ) -> &mut SearchOptions {
+        self.similarity_threshold = Some(similarity_threshold);
+        self
+    }
+
+    /// Sets whether results without any URLs should be filtered out.
+    pub fn empty_filter_enabled(&mut self, empty_filter_enabled: bool) -> &mut SearchOptions {
+        self.empty_filter_enabled = Some(empty_filter_enabled);
+        self
+    }
+
+    /// Sets how SauceNAO should filter out explicit results.
+    pub fn content_filter(&mut self, content_filter: ContentFilter) -> &mut SearchOptions {
+        self.content_filter = Some(content_filter);
+        self
+    }
+}
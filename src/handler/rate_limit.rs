@@ -0,0 +1,158 @@
+//! Tracking of the SauceNAO search limits, along with an opt-in rate limiter that keeps searches
+//! within them.
+
+use super::constants::{LONG_LIMIT_WINDOW, SHORT_LIMIT_WINDOW};
+use super::error::{Error, Result};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// The short (30 second) and long (24 hour) search limits, as last reported by SauceNAO.
+#[derive(Debug)]
+pub(in crate::handler) struct Limits {
+    pub short_limit: AtomicU32,
+    pub long_limit: AtomicU32,
+    pub short_left: AtomicU32,
+    pub long_left: AtomicU32,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            short_limit: AtomicU32::new(12),
+            long_limit: AtomicU32::new(200),
+            short_left: AtomicU32::new(12),
+            long_left: AtomicU32::new(200),
+        }
+    }
+}
+
+impl Limits {
+    /// Stores the limits reported by SauceNAO, keeping the previous value of any that were not.
+    pub fn update(
+        &self, short_limit: Option<u32>, long_limit: Option<u32>, short_left: Option<u32>,
+        long_left: Option<u32>,
+    ) {
+        let updates = [
+            (&self.short_limit, short_limit),
+            (&self.long_limit, long_limit),
+            (&self.short_left, short_left),
+            (&self.long_left, long_left),
+        ];
+        for (limit, value) in updates {
+            if let Some(value) = value {
+                limit.store(value, Ordering::SeqCst);
+            }
+        }
+    }
+}
+
+/// When the short and long limits are expected to reset, once they have been used up.
+#[derive(Debug, Default)]
+struct Resets {
+    short: Option<Instant>,
+    long: Option<Instant>,
+}
+
+/// A rate limiter that delays searches while the short limit is used up, and refuses them while
+/// the long limit is used up.
+#[derive(Debug, Default)]
+pub(in crate::handler) struct RateLimiter {
+    resets: Mutex<Resets>,
+}
+
+impl RateLimiter {
+    /// Reserves a search if one is available, otherwise returns how long to wait before trying
+    /// again.
+    fn try_reserve(&self, limits: &Limits) -> Result<Option<Duration>> {
+        let now = Instant::now();
+        let mut resets = self.resets.lock().unwrap();
+
+        if limits.long_left.load(Ordering::SeqCst) == 0 {
+            match resets.long {
+                Some(reset) if reset > now => {
+                    return Err(Error::LongLimitReached(format!(
+                        "no searches are left, the limit resets in {} seconds",
+                        (reset - now).as_secs()
+                    )));
+                }
+                _ => {
+                    let long_limit = limits.long_limit.load(Ordering::SeqCst);
+                    limits.long_left.store(long_limit, Ordering::SeqCst);
+                    resets.long = None;
+                }
+            }
+        }
+
+        if limits.short_left.load(Ordering::SeqCst) == 0 {
+            match resets.short {
+                Some(reset) if reset > now => return Ok(Some(reset - now)),
+                _ => {
+                    let short_limit = limits.short_limit.load(Ordering::SeqCst);
+                    limits.short_left.store(short_limit, Ordering::SeqCst);
+                    resets.short = None;
+                }
+            }
+        }
+
+        let short_left = Self::take_one(&limits.short_left);
+        let long_left = Self::take_one(&limits.long_left);
+        Self::start_windows(&mut resets, now, short_left, long_left);
+        Ok(None)
+    }
+
+    /// Takes one search from what is left of a limit, returning how many are left afterwards.
+    fn take_one(left: &AtomicU32) -> u32 {
+        let previous = left
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |x| {
+                Some(x.saturating_sub(1))
+            })
+            .unwrap_or_default();
+        previous.saturating_sub(1)
+    }
+
+    /// Starts waiting for a limit to reset if it has been used up, or stops waiting if it has not.
+    fn start_windows(resets: &mut Resets, now: Instant, short_left: u32, long_left: u32) {
+        Self::start_window(&mut resets.short, now + SHORT_LIMIT_WINDOW, short_left);
+        Self::start_window(&mut resets.long, now + LONG_LIMIT_WINDOW, long_left);
+    }
+
+    fn start_window(reset: &mut Option<Instant>, at: Instant, left: u32) {
+        if left > 0 {
+            *reset = None;
+        } else if reset.is_none() {
+            *reset = Some(at);
+        }
+    }
+
+    /// Waits until a search can be made without going over the short limit, and reserves it.
+    /// Returns an ``Error::LongLimitReached`` if the long limit has been used up.
+    pub async fn acquire(&self, limits: &Limits) -> Result<()> {
+        while let Some(wait) = self.try_reserve(limits)? {
+            tokio::time::sleep(wait).await;
+        }
+        Ok(())
+    }
+
+    /// Records the limits left after a search, as reported by SauceNAO.
+    pub fn record(&self, short_left: u32, long_left: u32) {
+        let mut resets = self.resets.lock().unwrap();
+        Self::start_windows(&mut resets, Instant::now(), short_left, long_left);
+    }
+
+    /// Records that SauceNAO reported a search limit as used up, so later searches wait for it.
+    pub fn record_error(&self, err: &Error, limits: &Limits) {
+        match err {
+            Error::ShortLimitReached(_) | Error::TooManyRequests { .. } => {
+                limits.short_left.store(0, Ordering::SeqCst)
+            }
+            Error::LongLimitReached(_) => limits.long_left.store(0, Ordering::SeqCst),
+            _ => return,
+        }
+        self.record(
+            limits.short_left.load(Ordering::SeqCst),
+            limits.long_left.load(Ordering::SeqCst),
+        );
+    }
+}
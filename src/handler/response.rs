@@ -0,0 +1,150 @@
+//! The SearchResponse struct, holding the results of a search along with what SauceNAO reported about it.
+
+use super::deserialize::{number_or_default, opt_number, ResultHeader};
+use super::sauce::Sauce;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The results of a search, along with the metadata SauceNAO returned with them.
+/// ## Example
+/// ```no_run
+/// use rustnao::HandlerBuilder;
+/// # tokio_test::block_on(async {
+/// let handle = HandlerBuilder::default().api_key("your_api_key").build();
+/// let response = handle.get_sauce_response("https://i.imgur.com/W42kkKS.jpg", None, None).await.unwrap();
+/// println!("Suggested minimum similarity: {:?}", response.meta.minimum_similarity);
+/// println!("Premium account: {}", response.meta.is_premium());
+/// for sauce in response.results {
+///     println!("{:?}", sauce);
+/// }
+/// # });
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResponse {
+    /// What SauceNAO reported about the search and the account that made it.
+    pub meta: SearchMeta,
+    /// The results of the search, after filtering.
+    pub results: Vec<Sauce>,
+    /// Any parts of the response that could not be read, such as results that were skipped for being malformed.
+    pub warnings: Vec<SearchWarning>,
+}
+
+/// A part of a search response that could not be read, and was skipped or left out instead of failing the search.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SearchWarning {
+    /// The position of the result the warning is about in the response, or ``None`` if it is about the header.
+    pub result: Option<usize>,
+    /// What could not be read.
+    pub message: String,
+}
+
+impl fmt::Display for SearchWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.result {
+            Some(x) => write!(f, "result {}: {}", x, self.message),
+            None => write!(f, "header: {}", self.message),
+        }
+    }
+}
+
+/// The metadata SauceNAO returns in the header of a search response.
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct SearchMeta {
+    /// The ID of the account that made the search.
+    pub user_id: Option<u64>,
+    /// The type of the account that made the search.  Free accounts are type 1, and paid accounts are higher.
+    pub account_type: Option<u32>,
+    /// The number of searches allowed every 30 seconds.
+    pub short_limit: Option<u32>,
+    /// The number of searches allowed every 24 hours.
+    pub long_limit: Option<u32>,
+    /// The number of searches left in the current 30 seconds.
+    pub short_remaining: Option<u32>,
+    /// The number of searches left in the current 24 hours.
+    pub long_remaining: Option<u32>,
+    /// The status SauceNAO returned.  Positive values mean some indexes failed on its end.
+    pub status: i32,
+    /// The message SauceNAO returned, if any.
+    pub message: Option<String>,
+    /// The number of results that were asked for.
+    pub results_requested: Option<u32>,
+    /// The number of results SauceNAO returned, before any filtering.
+    pub results_returned: Option<u32>,
+    /// How many entries SauceNAO searched through.
+    pub search_depth: Option<u64>,
+    /// The minimum similarity SauceNAO suggests for results to be considered matches.
+    pub minimum_similarity: Option<f64>,
+    /// The name SauceNAO gave the searched image.
+    pub query_image: Option<String>,
+    /// The path of the searched image on SauceNAO.
+    pub query_image_display: Option<String>,
+    /// The status of each index that was searched, by index number.
+    pub indexes: BTreeMap<u32, IndexStatus>,
+}
+
+impl SearchMeta {
+    /// Returns whether the search was made with a paid account.
+    pub fn is_premium(&self) -> bool {
+        self.account_type.is_some_and(|x| x > 1)
+    }
+
+    /// Returns the indexes that failed to be searched on SauceNAO's end.
+    pub fn failed_indexes(&self) -> Vec<u32> {
+        self.indexes
+            .iter()
+            .filter(|(_, index)| !index.is_ok())
+            .map(|(id, _)| *id)
+            .collect()
+    }
+}
+
+/// The status of a single index that was searched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IndexStatus {
+    /// The status of the index.  Anything other than 0 means it failed to be searched.
+    #[serde(deserialize_with = "number_or_default")]
+    pub status: i32,
+    /// The index this index is a part of, or its own number if it is not part of another.
+    #[serde(deserialize_with = "number_or_default")]
+    pub parent_id: u32,
+    /// The number of the index.
+    #[serde(deserialize_with = "number_or_default")]
+    pub id: u32,
+    /// The number of results taken from the index.
+    #[serde(deserialize_with = "opt_number")]
+    pub results: Option<u32>,
+}
+
+impl IndexStatus {
+    /// Returns whether the index was searched successfully.
+    pub fn is_ok(&self) -> bool {
+        self.status == 0
+    }
+}
+
+/// Creates the metadata of a search from the header of its response.
+pub(in crate::handler) fn new_meta(header: ResultHeader) -> SearchMeta {
+    SearchMeta {
+        user_id: header.user_id,
+        account_type: header.account_type,
+        short_limit: header.short_limit,
+        long_limit: header.long_limit,
+        short_remaining: header.short_remaining,
+        long_remaining: header.long_remaining,
+        status: header.status,
+        message: header.message.filter(|x| !x.is_empty()),
+        results_requested: header.results_requested,
+        results_returned: header.results_returned,
+        search_depth: header.search_depth,
+        minimum_similarity: header.minimum_similarity,
+        query_image: header.query_image,
+        query_image_display: header.query_image_display,
+        indexes: header
+            .index
+            .into_iter()
+            .filter_map(|(id, status)| Some((id.parse().ok()?, status)))
+            .collect(),
+    }
+}
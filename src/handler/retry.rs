@@ -0,0 +1,91 @@
+//! The RetryPolicy struct, used by the Handler to retry searches that fail for transient reasons.
+
+use super::error::Error;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// A policy for retrying searches that fail for transient reasons, with exponential backoff.
+/// ## Example
+/// ```
+/// use rustnao::{HandlerBuilder, RetryPolicy};
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy {
+///     max_attempts: 5,
+///     initial_backoff: Duration::from_millis(500),
+///     ..RetryPolicy::default()
+/// };
+/// let handle = HandlerBuilder::default().api_key("your_api_key").retry_policy(policy).build();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts made for a search, including the first one.
+    pub max_attempts: u32,
+    /// How long to wait before the first retry.  This doubles after every retry.
+    pub initial_backoff: Duration,
+    /// The longest to wait before any single retry.
+    pub max_backoff: Duration,
+    /// Whether to randomize how long is waited, so many clients failing at once do not all retry at the same time.
+    pub jitter: bool,
+    /// Whether to retry when the request could not be made or no response was received.
+    pub retry_network_errors: bool,
+    /// Whether to retry when SauceNAO reports a failure on its end, such as being overloaded.
+    pub retry_server_errors: bool,
+    /// Whether to retry when SauceNAO reports that the short (30 second) search limit has been reached.
+    pub retry_rate_limited: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+            retry_network_errors: true,
+            retry_server_errors: true,
+            retry_rate_limited: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns whether a search that failed with the given error on the given attempt (starting from 1) should be retried.
+    pub(in crate::handler) fn should_retry(&self, err: &Error, attempt: u32) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+
+        match err {
+            Error::InvalidRequest(_) => self.retry_network_errors,
+            Error::ServerError { .. } | Error::HttpServerError { .. } => self.retry_server_errors,
+            Error::ShortLimitReached(_) | Error::TooManyRequests { .. } => self.retry_rate_limited,
+            _ => false,
+        }
+    }
+
+    /// Returns how long to wait before retrying after the given attempt (starting from 1), waiting at least as long as the API asked to.
+    pub(in crate::handler) fn delay(&self, err: &Error, attempt: u32) -> Duration {
+        let backoff = self.backoff(attempt);
+        err.retry_after().map_or(backoff, |x| x.max(backoff))
+    }
+
+    /// Returns how long to back off for after the given attempt (starting from 1).
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(1 << exponent)
+            .min(self.max_backoff);
+
+        if self.jitter {
+            // Equal jitter, waiting at least half of the backoff.
+            let half = backoff / 2;
+            let random = RandomState::new().build_hasher().finish();
+            half + half.mul_f64(random as f64 / u64::MAX as f64)
+        } else {
+            backoff
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use serde::Serialize;
+use super::constants::Source;
+use super::source_data::{AnimeData, MangaData, SourceData};
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// A Sauce struct contains one result from a API call made by the Handler.  
@@ -10,7 +12,18 @@ use std::fmt;
 /// let result : rustnao::Result<Vec<Sauce>> = handle.get_sauce("https://i.pximg.net/img-master/img/2019/02/10/03/11/39/73095123_p0_master1200.jpg", None, None).await;
 /// # });
 /// ```
-#[derive(Serialize)]
+///
+/// Sauce objects serialize to JSON with a ``schema_version`` field (see [`Sauce::SCHEMA_VERSION`]), so stored results can be read back later.  JSON without a ``schema_version``, as written by older versions of rustnao, is read as version 1, and JSON from newer, unknown versions is rejected.
+/// ## Example
+/// ```
+/// use rustnao::Sauce;
+/// let json = r#"{"schema_version":1,"ext_urls":[],"title":null,"site":"Pixiv","index":5,"index_id":5,"similarity":92.51,"thumbnail":"","additional_fields":null,"source":null,"creator":null,"eng_name":null,"jp_name":null}"#;
+/// let sauce: Sauce = serde_json::from_str(json).unwrap();
+/// assert_eq!(sauce.site, "Pixiv");
+/// assert_eq!(serde_json::to_string(&sauce).unwrap(), json);
+/// ```
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(into = "SauceRepr", try_from = "SauceRepr")]
 pub struct Sauce {
     /// A Vec of Strings representing the external URLs for the image
     pub ext_urls: Vec<String>,
@@ -38,6 +51,80 @@ pub struct Sauce {
     pub jp_name: Option<String>,
 }
 
+/// The stored form of a Sauce, which carries the version of its schema.
+#[derive(Serialize, Deserialize)]
+struct SauceRepr {
+    #[serde(default = "first_schema_version")]
+    schema_version: u32,
+    #[serde(default)]
+    ext_urls: Vec<String>,
+    title: Option<String>,
+    site: String,
+    index: u32,
+    index_id: u32,
+    similarity: f32,
+    thumbnail: String,
+    additional_fields: Option<serde_json::Value>,
+    source: Option<String>,
+    creator: Option<Vec<String>>,
+    eng_name: Option<String>,
+    jp_name: Option<String>,
+}
+
+/// JSON written before the schema was versioned has no ``schema_version``, and matches version 1.
+fn first_schema_version() -> u32 {
+    1
+}
+
+impl From<Sauce> for SauceRepr {
+    fn from(sauce: Sauce) -> Self {
+        SauceRepr {
+            schema_version: Sauce::SCHEMA_VERSION,
+            ext_urls: sauce.ext_urls,
+            title: sauce.title,
+            site: sauce.site,
+            index: sauce.index,
+            index_id: sauce.index_id,
+            similarity: sauce.similarity,
+            thumbnail: sauce.thumbnail,
+            additional_fields: sauce.additional_fields,
+            source: sauce.source,
+            creator: sauce.creator,
+            eng_name: sauce.eng_name,
+            jp_name: sauce.jp_name,
+        }
+    }
+}
+
+impl TryFrom<SauceRepr> for Sauce {
+    type Error = String;
+
+    fn try_from(repr: SauceRepr) -> Result<Self, Self::Error> {
+        if repr.schema_version > Sauce::SCHEMA_VERSION {
+            return Err(format!(
+                "unsupported Sauce schema version {}, the latest supported is {}",
+                repr.schema_version,
+                Sauce::SCHEMA_VERSION
+            ));
+        }
+
+        Ok(Sauce {
+            ext_urls: repr.ext_urls,
+            title: repr.title,
+            site: repr.site,
+            index: repr.index,
+            index_id: repr.index_id,
+            similarity: repr.similarity,
+            thumbnail: repr.thumbnail,
+            additional_fields: repr.additional_fields,
+            source: repr.source,
+            creator: repr.creator,
+            eng_name: repr.eng_name,
+            jp_name: repr.jp_name,
+        })
+    }
+}
+
 // TODO: Consider making the sauce object a builder...
 
 /// Creates a new Sauce object.
@@ -65,6 +152,9 @@ pub(in crate::handler) fn new_sauce(
 }
 
 impl Sauce {
+    /// The version of the JSON schema Sauce objects are serialized with.  This is increased whenever the schema changes in a way older versions of rustnao could not read.
+    pub const SCHEMA_VERSION: u32 = 1;
+
     /// Returns whether the Sauce struct contains an empty ext_url field.
     /// ## Example
     /// ```
@@ -88,6 +178,98 @@ impl Sauce {
     pub fn has_empty_url(&self) -> bool {
         self.ext_urls.is_empty()
     }
+
+    /// Returns the fields specific to the source of the Sauce, typed for the major sources.  Sources without typed data, or whose fields could not be read, return ``SourceData::Other`` with the raw fields.
+    /// ## Example
+    /// ```no_run
+    /// use rustnao::{HandlerBuilder, SourceData};
+    /// # tokio_test::block_on(async {
+    /// let handle = HandlerBuilder::default().api_key("your_api_key").build();
+    /// let result = handle.get_sauce("https://i.imgur.com/W42kkKS.jpg", None, None).await.unwrap();
+    /// for sauce in result {
+    ///     match sauce.source_data() {
+    ///         SourceData::Pixiv(pixiv) => println!("Pixiv ID: {:?}", pixiv.pixiv_id),
+    ///         SourceData::Booru(booru) => println!("Characters: {:?}", booru.characters),
+    ///         other => println!("{:?}", other),
+    ///     }
+    /// }
+    /// # });
+    /// ```
+    pub fn source_data(&self) -> SourceData {
+        SourceData::new(Source::from_u32(self.index_id), self.source_fields())
+    }
+
+    /// Returns all the fields of the Sauce that can be specific to its source as a JSON object.
+    fn source_fields(&self) -> serde_json::Value {
+        let mut fields = match &self.additional_fields {
+            Some(serde_json::Value::Object(x)) => x.clone(),
+            _ => serde_json::Map::new(),
+        };
+
+        // The common fields are taken out of the source specific ones when deserializing, so
+        // they are put back for the sources that type them.
+        let common = [
+            ("title", self.title.clone().map(serde_json::Value::from)),
+            ("source", self.source.clone().map(serde_json::Value::from)),
+            ("creator", self.creator.clone().map(serde_json::Value::from)),
+            (
+                "eng_name",
+                self.eng_name.clone().map(serde_json::Value::from),
+            ),
+            ("jp_name", self.jp_name.clone().map(serde_json::Value::from)),
+        ];
+        for (key, value) in common {
+            if let Some(value) = value {
+                fields.entry(key).or_insert(value);
+            }
+        }
+
+        serde_json::Value::Object(fields)
+    }
+
+    /// Returns the anime specific data of the Sauce, if it is from Anime or H-Anime.
+    /// ## Example
+    /// ```no_run
+    /// use rustnao::{HandlerBuilder, Source};
+    /// # tokio_test::block_on(async {
+    /// let handle = HandlerBuilder::default().api_key("your_api_key").db_mask(vec![Source::Anime]).build();
+    /// let result = handle.get_sauce("https://i.imgur.com/W42kkKS.jpg", None, None).await.unwrap();
+    /// for anime in result.iter().filter_map(|sauce| sauce.anime()) {
+    ///     println!("{:?} episode {:?} at {:?}", anime.source, anime.episode(), anime.timestamp());
+    /// }
+    /// # });
+    /// ```
+    pub fn anime(&self) -> Option<AnimeData> {
+        match self.source_data() {
+            SourceData::Anime(anime) => Some(anime),
+            _ => None,
+        }
+    }
+
+    /// Returns a view of the Sauce as a manga or doujin, if it is from MangaDex, Madokami, DoujinshiDB, E-Hentai or H-Magazines.
+    /// ## Example
+    /// ```no_run
+    /// use rustnao::{HandlerBuilder, Source};
+    /// # tokio_test::block_on(async {
+    /// let handle = HandlerBuilder::default().api_key("your_api_key").db_mask(vec![Source::MangaDex]).build();
+    /// let result = handle.get_sauce("https://i.imgur.com/W42kkKS.jpg", None, None).await.unwrap();
+    /// for manga in result.iter().filter_map(|sauce| sauce.manga()) {
+    ///     println!("{:?} chapter {:?}, MangaDex ID {:?}", manga.series(), manga.chapter(), manga.md_id);
+    /// }
+    /// # });
+    /// ```
+    pub fn manga(&self) -> Option<MangaData> {
+        match Source::from_u32(self.index_id)? {
+            Source::MangaDex
+            | Source::MangaDexV2
+            | Source::Madokami
+            | Source::DoujinshiDB
+            | Source::DoujinshiDBSamples
+            | Source::EHentai
+            | Source::HMagazines => serde_json::from_value(self.source_fields()).ok(),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Debug for Sauce {
@@ -100,12 +282,9 @@ impl fmt::Debug for Sauce {
         }
         result.push_str("\nsite: ");
         result.push_str(self.site.as_str());
-        match &self.title {
-            Some(x) => {
-                result.push_str("\ntitle: ");
-                result.push_str(x.as_str());
-            }
-            None => (),
+        if let Some(x) = &self.title {
+            result.push_str("\ntitle: ");
+            result.push_str(x.as_str());
         }
         result.push_str("\nindex: ");
         result.push_str(self.index.to_string().as_str());
@@ -122,4 +301,4 @@ impl fmt::Debug for Sauce {
 
         write!(f, "{}", result)
     }
-}
\ No newline at end of file
+}
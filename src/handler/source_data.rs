@@ -0,0 +1,389 @@
+//! Typed data for the fields specific to a source, which SauceNAO returns alongside the common ones.
+
+use super::constants::Source;
+use super::deserialize::{opt_number, opt_string};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Data specific to Pixiv results.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PixivData {
+    /// The ID of the illustration on Pixiv.
+    #[serde(deserialize_with = "opt_number")]
+    pub pixiv_id: Option<u64>,
+    /// The name of the artist.
+    #[serde(deserialize_with = "opt_string")]
+    pub member_name: Option<String>,
+    /// The ID of the artist on Pixiv.
+    #[serde(deserialize_with = "opt_number")]
+    pub member_id: Option<u64>,
+}
+
+/// Data specific to results from image boorus, such as Danbooru, Gelbooru and Yande.re.  SauceNAO merges posts of the same image from several boorus into one result, so a result can have the ID of more than one booru.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BooruData {
+    /// The ID of the post on Danbooru.
+    #[serde(deserialize_with = "opt_number")]
+    pub danbooru_id: Option<u64>,
+    /// The ID of the post on Gelbooru.
+    #[serde(deserialize_with = "opt_number")]
+    pub gelbooru_id: Option<u64>,
+    /// The ID of the post on Yande.re.
+    #[serde(deserialize_with = "opt_number")]
+    pub yandere_id: Option<u64>,
+    /// The ID of the post on Konachan.
+    #[serde(deserialize_with = "opt_number")]
+    pub konachan_id: Option<u64>,
+    /// The ID of the post on Sankaku Channel.
+    #[serde(deserialize_with = "opt_number")]
+    pub sankaku_id: Option<u64>,
+    /// The ID of the post on e621.net.
+    #[serde(deserialize_with = "opt_number")]
+    pub e621_id: Option<u64>,
+    /// The ID of the post on Idol Complex.
+    #[serde(deserialize_with = "opt_number")]
+    pub idol_id: Option<u64>,
+    /// The ID of the post on Anime-Pictures.net.
+    #[serde(rename = "anime-pictures_id", deserialize_with = "opt_number")]
+    pub anime_pictures_id: Option<u64>,
+    /// The artists of the post.
+    pub creator: Vec<String>,
+    /// The series (copyright tags) of the post.
+    #[serde(deserialize_with = "opt_string")]
+    pub material: Option<String>,
+    /// The characters in the post.
+    #[serde(deserialize_with = "opt_string")]
+    pub characters: Option<String>,
+    /// Where the post says the image originally came from.
+    #[serde(deserialize_with = "opt_string")]
+    pub source: Option<String>,
+}
+
+impl BooruData {
+    /// Returns the ID of the post on each booru it was found on, in the order SauceNAO lists the boorus.
+    /// ## Example
+    /// ```
+    /// use rustnao::{BooruData, Source};
+    /// let booru = BooruData { danbooru_id: Some(1234), gelbooru_id: Some(5678), ..Default::default() };
+    /// assert_eq!(booru.ids(), vec![(Source::Danbooru, 1234), (Source::Gelbooru, 5678)]);
+    /// assert_eq!(booru.id(), Some(1234));
+    /// ```
+    pub fn ids(&self) -> Vec<(Source, u64)> {
+        [
+            (Source::Danbooru, self.danbooru_id),
+            (Source::Gelbooru, self.gelbooru_id),
+            (Source::Yandere, self.yandere_id),
+            (Source::Konachan, self.konachan_id),
+            (Source::SankakuChannel, self.sankaku_id),
+            (Source::E621Net, self.e621_id),
+            (Source::IdolComplex, self.idol_id),
+            (Source::AnimePicturesNet, self.anime_pictures_id),
+        ]
+        .into_iter()
+        .filter_map(|(source, id)| Some((source, id?)))
+        .collect()
+    }
+
+    /// Returns the ID of the post on the first booru it was found on.
+    pub fn id(&self) -> Option<u64> {
+        self.ids().first().map(|(_, id)| *id)
+    }
+}
+
+/// Data specific to Anime and H-Anime results.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnimeData {
+    /// The name of the anime.
+    #[serde(deserialize_with = "opt_string")]
+    pub source: Option<String>,
+    /// The ID of the anime on AniDB.
+    #[serde(deserialize_with = "opt_number")]
+    pub anidb_aid: Option<u64>,
+    /// The ID of the anime on MyAnimeList.
+    #[serde(deserialize_with = "opt_number")]
+    pub mal_id: Option<u64>,
+    /// The ID of the anime on AniList.
+    #[serde(deserialize_with = "opt_number")]
+    pub anilist_id: Option<u64>,
+    /// The episode the match is from.
+    #[serde(deserialize_with = "opt_string")]
+    pub part: Option<String>,
+    /// The year the anime aired.
+    #[serde(deserialize_with = "opt_string")]
+    pub year: Option<String>,
+    /// The estimated time of the match in the episode, such as ``00:12:34 / 00:23:40``.
+    #[serde(deserialize_with = "opt_string")]
+    pub est_time: Option<String>,
+}
+
+/// Parses the first run of digits in a string as a number.
+fn first_number(text: &str) -> Option<u32> {
+    let start = text.find(|c: char| c.is_ascii_digit())?;
+    let digits = &text[start..];
+    let end = digits
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(digits.len());
+    digits[..end].parse().ok()
+}
+
+/// Parses a timestamp such as ``01:02:03``, ``02:03`` or ``02:03.5`` into a Duration.
+fn parse_timestamp(text: &str) -> Option<Duration> {
+    let mut seconds = 0.0;
+    for part in text.trim().split(':') {
+        let value: f64 = part.trim().parse().ok()?;
+        if !value.is_finite() || value < 0.0 {
+            return None;
+        }
+        seconds = seconds * 60.0 + value;
+    }
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+impl AnimeData {
+    /// Returns the episode number the match is from.
+    /// ## Example
+    /// ```
+    /// use rustnao::AnimeData;
+    /// let anime = AnimeData { part: Some("12".to_string()), ..Default::default() };
+    /// assert_eq!(anime.episode(), Some(12));
+    /// ```
+    pub fn episode(&self) -> Option<u32> {
+        self.part.as_deref().and_then(first_number)
+    }
+
+    /// Returns the year the anime started airing.
+    /// ## Example
+    /// ```
+    /// use rustnao::AnimeData;
+    /// let anime = AnimeData { year: Some("2006-2007".to_string()), ..Default::default() };
+    /// assert_eq!(anime.year(), Some(2006));
+    /// ```
+    pub fn year(&self) -> Option<u32> {
+        self.year.as_deref().and_then(first_number)
+    }
+
+    /// Returns when in the episode the match is estimated to be.
+    /// ## Example
+    /// ```
+    /// use rustnao::AnimeData;
+    /// use std::time::Duration;
+    /// let anime = AnimeData { est_time: Some("00:12:34 / 00:23:40".to_string()), ..Default::default() };
+    /// assert_eq!(anime.timestamp(), Some(Duration::from_secs(12 * 60 + 34)));
+    /// assert_eq!(anime.total_length(), Some(Duration::from_secs(23 * 60 + 40)));
+    /// ```
+    pub fn timestamp(&self) -> Option<Duration> {
+        let est_time = self.est_time.as_deref()?;
+        parse_timestamp(est_time.split('/').next()?)
+    }
+
+    /// Returns the total length of the episode, if SauceNAO knows it.
+    pub fn total_length(&self) -> Option<Duration> {
+        let est_time = self.est_time.as_deref()?;
+        parse_timestamp(est_time.split('/').nth(1)?)
+    }
+}
+
+/// A view of a manga or doujin result, from MangaDex, Madokami, DoujinshiDB, E-Hentai or H-Magazines.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MangaData {
+    /// The title of the result.  This is the chapter title for MangaDex, and the series or issue for the others.
+    #[serde(deserialize_with = "opt_string")]
+    pub title: Option<String>,
+    /// The name of the series, for the sources that give one separately from the title.
+    #[serde(deserialize_with = "opt_string")]
+    pub source: Option<String>,
+    /// The chapter or part the match is from, such as `` - Chapter 5``.
+    #[serde(deserialize_with = "opt_string")]
+    pub part: Option<String>,
+    /// The kind of publication, such as ``Manga`` or ``Doujinshi``.
+    #[serde(rename = "type", deserialize_with = "opt_string")]
+    pub kind: Option<String>,
+    /// The ID of the chapter on MangaDex.
+    #[serde(deserialize_with = "opt_string")]
+    pub md_id: Option<String>,
+    /// The ID of the series on MangaUpdates.
+    #[serde(deserialize_with = "opt_number")]
+    pub mu_id: Option<u64>,
+    /// The ID of the series on MyAnimeList.
+    #[serde(deserialize_with = "opt_number")]
+    pub mal_id: Option<u64>,
+    /// The artist of the series.
+    #[serde(deserialize_with = "opt_string")]
+    pub artist: Option<String>,
+    /// The author of the series.
+    #[serde(deserialize_with = "opt_string")]
+    pub author: Option<String>,
+    /// The artists and circles of the work.
+    pub creator: Vec<String>,
+    /// When the work was published.
+    #[serde(deserialize_with = "opt_string")]
+    pub date: Option<String>,
+}
+
+impl MangaData {
+    /// Returns the name of the series, falling back to the title for the sources that do not give one separately.
+    pub fn series(&self) -> Option<&str> {
+        self.source.as_deref().or(self.title.as_deref())
+    }
+
+    /// Returns the chapter number the match is from, parsed from the part.
+    /// ## Example
+    /// ```
+    /// use rustnao::MangaData;
+    /// let manga = MangaData { part: Some(" - Chapter 5".to_string()), ..Default::default() };
+    /// assert_eq!(manga.chapter(), Some(5));
+    /// ```
+    pub fn chapter(&self) -> Option<u32> {
+        self.part.as_deref().and_then(first_number)
+    }
+}
+
+/// Data specific to Twitter results.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TwitterData {
+    /// When the tweet was posted.
+    #[serde(deserialize_with = "opt_string")]
+    pub created_at: Option<String>,
+    /// The ID of the tweet.
+    #[serde(deserialize_with = "opt_string")]
+    pub tweet_id: Option<String>,
+    /// The ID of the user who posted the tweet.
+    #[serde(deserialize_with = "opt_string")]
+    pub twitter_user_id: Option<String>,
+    /// The handle of the user who posted the tweet.
+    #[serde(deserialize_with = "opt_string")]
+    pub twitter_user_handle: Option<String>,
+}
+
+/// Data specific to E-Hentai results.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EHentaiData {
+    /// The name of the gallery.
+    #[serde(deserialize_with = "opt_string")]
+    pub source: Option<String>,
+    /// The artists and circles of the gallery.
+    pub creator: Vec<String>,
+    /// The name of the gallery in English/romanized.
+    #[serde(deserialize_with = "opt_string")]
+    pub eng_name: Option<String>,
+    /// The original name of the gallery in Japanese.
+    #[serde(deserialize_with = "opt_string")]
+    pub jp_name: Option<String>,
+}
+
+/// Data specific to deviantArt results.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeviantArtData {
+    /// The ID of the deviation.
+    #[serde(deserialize_with = "opt_string")]
+    pub da_id: Option<String>,
+    /// The name of the artist.
+    #[serde(deserialize_with = "opt_string")]
+    pub author_name: Option<String>,
+    /// The URL of the artist's profile.
+    #[serde(deserialize_with = "opt_string")]
+    pub author_url: Option<String>,
+}
+
+/// Data specific to FurAffinity results.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FurAffinityData {
+    /// The ID of the submission.
+    #[serde(deserialize_with = "opt_number")]
+    pub fa_id: Option<u64>,
+    /// The name of the artist.
+    #[serde(deserialize_with = "opt_string")]
+    pub author_name: Option<String>,
+    /// The URL of the artist's profile.
+    #[serde(deserialize_with = "opt_string")]
+    pub author_url: Option<String>,
+}
+
+/// Data specific to Skeb results.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SkebData {
+    /// The path of the work on Skeb, such as ``/@creator/works/1``.
+    #[serde(deserialize_with = "opt_string")]
+    pub path: Option<String>,
+    /// The display name of the creator.
+    #[serde(deserialize_with = "opt_string")]
+    pub creator_name: Option<String>,
+    /// The name of the author, if SauceNAO has one.
+    #[serde(deserialize_with = "opt_string")]
+    pub author_name: Option<String>,
+    /// The URL of the creator's profile.
+    #[serde(deserialize_with = "opt_string")]
+    pub author_url: Option<String>,
+}
+
+/// The fields specific to the source of a result, typed for the major sources.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SourceData {
+    /// Data from Pixiv.
+    Pixiv(PixivData),
+    /// Data from an image booru, such as Danbooru, Gelbooru or Yande.re.
+    Booru(BooruData),
+    /// Data from Anime or H-Anime.
+    Anime(AnimeData),
+    /// Data from MangaDex, read as the same manga view that ``Sauce::manga`` returns.
+    MangaDex(MangaData),
+    /// Data from Twitter.
+    Twitter(TwitterData),
+    /// Data from E-Hentai.
+    EHentai(EHentaiData),
+    /// Data from deviantArt.
+    DeviantArt(DeviantArtData),
+    /// Data from FurAffinity.
+    FurAffinity(FurAffinityData),
+    /// Data from Skeb.
+    Skeb(SkebData),
+    /// The raw fields of a source without typed data, or whose fields could not be read.
+    Other(serde_json::Value),
+}
+
+/// Deserializes typed data from the fields of a result, wrapping it in a SourceData variant.
+fn typed<T: DeserializeOwned>(
+    fields: &serde_json::Value, variant: fn(T) -> SourceData,
+) -> SourceData {
+    match T::deserialize(fields) {
+        Ok(data) => variant(data),
+        Err(_) => SourceData::Other(fields.clone()),
+    }
+}
+
+impl SourceData {
+    /// Creates the SourceData for the given source from the fields of a result.
+    pub(in crate::handler) fn new(source: Option<Source>, fields: serde_json::Value) -> SourceData {
+        match source {
+            Some(Source::Pixiv) => typed(&fields, SourceData::Pixiv),
+            Some(
+                Source::Danbooru
+                | Source::Gelbooru
+                | Source::Yandere
+                | Source::Konachan
+                | Source::SankakuChannel
+                | Source::E621Net
+                | Source::IdolComplex
+                | Source::AnimePicturesNet,
+            ) => typed(&fields, SourceData::Booru),
+            Some(Source::Anime | Source::HAnime) => typed(&fields, SourceData::Anime),
+            Some(Source::MangaDex | Source::MangaDexV2) => typed(&fields, SourceData::MangaDex),
+            Some(Source::Twitter) => typed(&fields, SourceData::Twitter),
+            Some(Source::EHentai) => typed(&fields, SourceData::EHentai),
+            Some(Source::DeviantArt) => typed(&fields, SourceData::DeviantArt),
+            Some(Source::FurAffinity) => typed(&fields, SourceData::FurAffinity),
+            Some(Source::Skeb) => typed(&fields, SourceData::Skeb),
+            _ => SourceData::Other(fields),
+        }
+    }
+}
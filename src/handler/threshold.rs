@@ -0,0 +1,32 @@
+//! The SimilarityThreshold enum, deciding which minimum similarity the Handler filters results with.
+
+/// How the minimum similarity that results are filtered with is decided.
+///
+/// SauceNAO suggests a minimum similarity with every search, above which it considers results to be confident matches.
+/// ## Example
+/// ```
+/// use rustnao::{HandlerBuilder, SimilarityThreshold};
+/// // Only keep results that SauceNAO is confident about, and that are at least 60% similar.
+/// let handle = HandlerBuilder::default().api_key("your_api_key").min_similarity(60).similarity_threshold(SimilarityThreshold::Max).build();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SimilarityThreshold {
+    /// Only use the minimum similarity that was set on the Handler or given to the search.
+    #[default]
+    Fixed,
+    /// Use the minimum similarity suggested by SauceNAO instead of the one set on the Handler, falling back to the set one if none was suggested.  A minimum similarity given to a single search is not replaced, and the higher of it and the suggestion is used, as with ``Max``.
+    ServerSuggested,
+    /// Use whichever is higher of the set minimum similarity and the one suggested by SauceNAO.
+    Max,
+}
+
+impl SimilarityThreshold {
+    /// Returns the minimum similarity to filter with, given the set one and the one suggested by SauceNAO.
+    pub(in crate::handler) fn resolve(&self, fixed: f64, suggested: Option<f64>) -> f64 {
+        match (self, suggested) {
+            (SimilarityThreshold::ServerSuggested, Some(x)) => x,
+            (SimilarityThreshold::Max, Some(x)) => fixed.max(x),
+            _ => fixed,
+        }
+    }
+}
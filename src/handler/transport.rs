@@ -0,0 +1,127 @@
+//! The transport layer used by the Handler to talk to the SauceNAO API, along with the default
+//! implementation backed by reqwest.
+
+use bytes::Bytes;
+use reqwest::header::HeaderMap;
+use reqwest::{Client, StatusCode};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use url::Url;
+
+/// A boxed error type returned by a [`Transport`].
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A boxed future returned by a [`Transport`].
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A file uploaded alongside a search request.
+#[derive(Debug, Clone)]
+pub struct Upload {
+    /// The name of the uploaded file.
+    pub file_name: String,
+    /// The contents of the uploaded file.  Cloning the upload shares this buffer instead of copying it, so retrying a request does not copy the image again.
+    pub data: Bytes,
+}
+
+/// A search request for a [`Transport`] to send to the SauceNAO API.
+#[derive(Debug, Clone)]
+pub struct TransportRequest {
+    /// The full URL of the request, including all query parameters.
+    pub url: Url,
+    /// The image to upload as the multipart ``file`` field, if one is not passed by URL.
+    pub upload: Option<Upload>,
+}
+
+/// A raw response returned by a [`Transport`].
+#[derive(Debug, Clone)]
+pub struct TransportResponse {
+    /// The HTTP status code of the response.
+    pub status: StatusCode,
+    /// The headers of the response.
+    pub headers: HeaderMap,
+    /// The raw body of the response, usually JSON.
+    pub body: Vec<u8>,
+}
+
+impl TransportResponse {
+    /// Creates a response with a status code and body, and no headers.
+    pub fn new(status: StatusCode, body: impl Into<Vec<u8>>) -> TransportResponse {
+        TransportResponse {
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+}
+
+/// A trait for sending search requests to the SauceNAO API, so the Handler can be used with a
+/// different HTTP stack or a fake backend.
+/// ## Example
+/// ```
+/// use rustnao::{BoxError, BoxFuture, HandlerBuilder, Transport, TransportRequest, TransportResponse};
+/// use rustnao::reqwest::StatusCode;
+///
+/// #[derive(Debug)]
+/// struct Canned;
+///
+/// impl Transport for Canned {
+///     fn send(&self, _request: TransportRequest) -> BoxFuture<'_, Result<TransportResponse, BoxError>> {
+///         Box::pin(async {
+///             let body = r#"{"header": {"status": 0, "short_limit": "4", "long_limit": "100"}, "results": []}"#;
+///             Ok(TransportResponse::new(StatusCode::OK, body))
+///         })
+///     }
+/// }
+///
+/// # tokio_test::block_on(async {
+/// let handle = HandlerBuilder::default().transport(Canned).build();
+/// assert!(handle.get_sauce("https://i.imgur.com/W42kkKS.jpg", None, None).await.unwrap().is_empty());
+/// # });
+/// ```
+pub trait Transport: fmt::Debug + Send + Sync {
+    /// Sends a search request, returning the raw response.
+    fn send(&self, request: TransportRequest)
+        -> BoxFuture<'_, Result<TransportResponse, BoxError>>;
+}
+
+/// The default [`Transport`], which sends requests using a ``reqwest::Client``.
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    /// Creates a transport that sends requests with the given client.
+    pub fn new(client: Client) -> ReqwestTransport {
+        ReqwestTransport { client }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(
+        &self, request: TransportRequest,
+    ) -> BoxFuture<'_, Result<TransportResponse, BoxError>> {
+        Box::pin(async move {
+            let builder = self.client.post(request.url);
+            let builder = match request.upload {
+                Some(upload) => {
+                    let part =
+                        reqwest::multipart::Part::stream(upload.data).file_name(upload.file_name);
+                    builder.multipart(reqwest::multipart::Form::new().part("file", part))
+                }
+                None => builder,
+            };
+
+            let response = builder.send().await?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            let body = response.bytes().await?.to_vec();
+            Ok(TransportResponse {
+                status,
+                headers,
+                body,
+            })
+        })
+    }
+}
@@ -44,4 +44,15 @@
 #![deny(missing_docs)]
 
 mod handler;
-pub use handler::{Error, Handler, HandlerBuilder, Result, Sauce, Source, ToJSON};
\ No newline at end of file
+pub use handler::{
+    AnimeData, BooruData, BoxError, BoxFuture, ContentFilter, DbMask, DeviantArtData, EHentaiData,
+    Error, FurAffinityData, Handler, HandlerBuilder, IndexStatus, MangaData, PixivData,
+    ReqwestTransport, Result, RetryPolicy, Sauce, SearchInput, SearchMeta, SearchOptions,
+    SearchResponse, SearchWarning, SimilarityThreshold, SkebData, Source, SourceCategory,
+    SourceData, ToJSON, Transport, TransportRequest, TransportResponse, TwitterData, Upload,
+};
+
+/// Re-export of the ``bytes`` crate, for the shared buffer holding an [`Upload`].
+pub use bytes;
+/// Re-export of the ``reqwest`` crate, for configuring the HTTP client used by a Handler.
+pub use reqwest;
@@ -21,7 +21,8 @@ fn create_handler(
     }
 
     let mut builder = HandlerBuilder::default();
-    builder.db_mask(db_mask)
+    builder
+        .db_mask(db_mask)
         .db_mask_i(db_mask_i)
         .num_results(numres)
         .api_key(api_key.as_str());
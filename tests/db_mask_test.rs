@@ -0,0 +1,123 @@
+use rustnao::{DbMask, Error, Source};
+
+/// Tests encoding masks as decimal, including indices above 63
+#[test]
+fn test_encode_mask() {
+    assert_eq!(DbMask::new().to_string(), "0");
+    assert_eq!(DbMask::from(Source::Pixiv).to_string(), "32");
+    assert_eq!(
+        DbMask::from(Source::Unknown(64)).to_string(),
+        "18446744073709551616"
+    );
+    assert_eq!(
+        DbMask::from(Source::Unknown(127)).to_string(),
+        "170141183460469231731687303715884105728"
+    );
+    assert_eq!(
+        DbMask::from(vec![Source::HMagazines, Source::Unknown(64)]).to_string(),
+        "18446744073709551617"
+    );
+}
+
+/// Tests parsing masks from decimal
+#[test]
+fn test_parse_mask() {
+    let masks = vec![
+        DbMask::new(),
+        DbMask::all(),
+        DbMask::from(vec![
+            Source::Skeb,
+            Source::Unknown(64),
+            Source::Unknown(200),
+        ]),
+        DbMask::from(Source::Unknown(1000)),
+    ];
+    for mask in masks {
+        assert_eq!(mask.to_string().parse::<DbMask>().unwrap(), mask);
+    }
+
+    assert_eq!("000544".parse::<DbMask>().unwrap().to_string(), "544");
+    assert!(matches!(
+        "-1".parse::<DbMask>(),
+        Err(Error::InvalidParameters(_))
+    ));
+    assert!("".parse::<DbMask>().is_err());
+    assert!("12a".parse::<DbMask>().is_err());
+}
+
+/// Tests set operations on masks
+#[test]
+fn test_mask_operations() {
+    let art: DbMask = [Source::Pixiv, Source::DeviantArt, Source::Unknown(70)]
+        .into_iter()
+        .collect();
+    let boorus = DbMask::from(vec![Source::Danbooru, Source::Pixiv]);
+
+    assert_eq!(
+        art.union(&boorus).iter().collect::<Vec<_>>(),
+        vec![
+            Source::Pixiv,
+            Source::Danbooru,
+            Source::DeviantArt,
+            Source::Unknown(70)
+        ]
+    );
+    assert_eq!(art.intersection(&boorus), DbMask::from(Source::Pixiv));
+    assert_eq!(
+        art.difference(&boorus),
+        DbMask::from(vec![Source::DeviantArt, Source::Unknown(70)])
+    );
+    assert_eq!(boorus.difference(&boorus), DbMask::new());
+
+    let complement = boorus.complement();
+    assert_eq!(complement.len(), Source::all().len() - 2);
+    assert!(!complement.contains(Source::Danbooru));
+    assert!(complement.contains(Source::Gelbooru));
+    assert_eq!(complement.union(&boorus), DbMask::all());
+}
+
+/// Tests adding and removing sources
+#[test]
+fn test_mask_insert_remove() {
+    let mut mask = DbMask::new();
+    assert!(mask.is_empty());
+    mask.insert(Source::Unknown(130));
+    mask.extend([Source::Anime, Source::Anime]);
+    assert_eq!(mask.len(), 2);
+    assert!(mask.contains(Source::Unknown(130)));
+
+    mask.remove(Source::Unknown(130));
+    assert_eq!(mask, DbMask::from(Source::Anime));
+    mask.remove(Source::Anime);
+    assert!(mask.is_empty());
+    assert_eq!(mask, DbMask::new());
+}
+
+/// Tests that indices above the maximum are not stored, parsed or deserialized
+#[test]
+fn test_mask_huge_index() {
+    let mut mask = DbMask::new();
+    mask.insert(Source::Unknown(4_000_000_000));
+    mask.insert(Source::Unknown(Source::MAX_INDEX + 1));
+    assert!(mask.is_empty());
+    assert!(!mask.contains(Source::Unknown(4_000_000_000)));
+    mask.insert(Source::Unknown(Source::MAX_INDEX));
+    assert_eq!(
+        mask.iter().collect::<Vec<_>>(),
+        vec![Source::Unknown(Source::MAX_INDEX)]
+    );
+
+    assert!(matches!(
+        "4000000000".parse::<Source>(),
+        Err(Error::InvalidParameters(_))
+    ));
+    assert!("99999999999999999999".parse::<Source>().is_err());
+    assert!(serde_json::from_str::<Source>("4000000000").is_err());
+
+    let too_large = DbMask::from(Source::Unknown(Source::MAX_INDEX)).to_string() + "0";
+    assert!(matches!(
+        too_large.parse::<DbMask>(),
+        Err(Error::InvalidParameters(_))
+    ));
+    assert_eq!(mask.to_string().parse::<DbMask>().unwrap(), mask);
+}
@@ -1,4 +1,4 @@
-use rustnao::{Handler, HandlerBuilder, Source};
+use rustnao::{Error, Handler, HandlerBuilder, Source};
 
 const FILE: &str = "https://i.imgur.com/W42kkKS.jpg";
 const INVALID_URL: &str = "https://j.jmgur.com";
@@ -24,7 +24,8 @@ fn create_handler(
     }
 
     let mut builder = HandlerBuilder::default();
-    builder.db_mask(db_mask)
+    builder
+        .db_mask(db_mask)
         .db_mask_i(db_mask_i)
         .num_results(numres)
         .api_key(api_key.as_str());
@@ -73,7 +74,7 @@ async fn test_invalid_file_json() {
 async fn test_invalid_num_results() {
     let handle = create_handler(vec![], vec![], Some(999), 2);
     let vec = handle.get_sauce(FILE, Some(1000), None).await;
-    assert!(vec.is_err());
+    assert!(matches!(vec, Err(Error::InvalidParameters(_))));
 }
 
 /// Tests an invalid minimum similarity option (upper)
@@ -81,7 +82,7 @@ async fn test_invalid_num_results() {
 async fn test_invalid_min_similarity_upper() {
     let handle = create_handler(vec![], vec![], Some(999), 2);
     let vec_two = handle.get_sauce(FILE, None, Some(100.1)).await;
-    assert!(vec_two.is_err());
+    assert!(matches!(vec_two, Err(Error::InvalidParameters(_))));
 }
 
 /// Tests an invalid minimum similarity option (lower)
@@ -89,5 +90,61 @@ async fn test_invalid_min_similarity_upper() {
 async fn test_invalid_min_similarity_lower() {
     let handle = create_handler(vec![], vec![], Some(999), 2);
     let vec_two = handle.get_sauce(FILE, None, Some(-0.1)).await;
-    assert!(vec_two.is_err());
-}
\ No newline at end of file
+    assert!(matches!(vec_two, Err(Error::InvalidParameters(_))));
+}
+
+/// Tests an invalid base URL
+#[test]
+fn test_invalid_base_url() {
+    let result = HandlerBuilder::default().base_url("not a url").try_build();
+    assert!(result.is_err());
+    let result = HandlerBuilder::default()
+        .base_url("ftp://localhost/search.php")
+        .try_build();
+    assert!(result.is_err());
+}
+
+/// Tests combining a pre-built client with HTTP settings
+#[test]
+fn test_client_with_http_settings() {
+    let result = HandlerBuilder::default()
+        .client(rustnao::reqwest::Client::new())
+        .timeout(std::time::Duration::from_secs(5))
+        .try_build();
+    assert!(result.is_err());
+}
+
+/// Tests invalid number of results and minimum similarity options on the builder
+#[test]
+fn test_invalid_builder_options() {
+    let result = HandlerBuilder::default().num_results(1000).try_build();
+    assert!(matches!(result, Err(Error::InvalidParameters(_))));
+    let result = HandlerBuilder::default().min_similarity(100.1).try_build();
+    assert!(matches!(result, Err(Error::InvalidParameters(_))));
+    let result = HandlerBuilder::default().min_similarity(-0.1).try_build();
+    assert!(matches!(result, Err(Error::InvalidParameters(_))));
+}
+
+/// Tests that build does not panic on invalid options, which fail the search instead
+#[tokio::test]
+async fn test_invalid_builder_options_at_search() {
+    let handle = HandlerBuilder::default().num_results(1000).build();
+    let result = handle.get_sauce(FILE, None, None).await;
+    assert!(matches!(result, Err(Error::InvalidParameters(_))));
+    let handle = HandlerBuilder::default().min_similarity(100.1).build();
+    let result = handle.get_sauce(FILE, None, None).await;
+    assert!(matches!(result, Err(Error::InvalidParameters(_))));
+}
+
+/// Tests setting an invalid minimum similarity on an existing handler
+#[test]
+fn test_invalid_set_min_similarity() {
+    let handle = HandlerBuilder::default().build();
+    assert!(handle.try_set_min_similarity(50).is_ok());
+    for invalid in [100.1, -0.1, f64::NAN] {
+        assert!(matches!(
+            handle.try_set_min_similarity(invalid),
+            Err(Error::InvalidParameters(_))
+        ));
+    }
+}
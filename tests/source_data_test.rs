@@ -0,0 +1,307 @@
+use rustnao::reqwest::StatusCode;
+use rustnao::{
+    AnimeData, BoxError, BoxFuture, HandlerBuilder, Sauce, Source, SourceData, ToJSON, Transport,
+    TransportRequest, TransportResponse,
+};
+use std::time::Duration;
+
+const FILE: &str = "https://i.imgur.com/W42kkKS.jpg";
+
+const RESPONSE: &str = r#"{
+    "header": {
+        "short_limit": "4",
+        "long_limit": "100",
+        "long_remaining": 99,
+        "short_remaining": 3,
+        "status": 0
+    },
+    "results": [
+        {
+            "header": {
+                "similarity": "92.51",
+                "thumbnail": "https://img1.saucenao.com/res/pixiv/6147/61477678_p0.jpg",
+                "index_id": 5,
+                "index_name": "Index #5: Pixiv Images - 61477678_p0.jpg"
+            },
+            "data": {
+                "ext_urls": ["https://www.pixiv.net/member_illust.php?mode=medium&illust_id=61477678"],
+                "title": "Example",
+                "pixiv_id": 61477678,
+                "member_name": "Example Artist",
+                "member_id": 4754550
+            }
+        },
+        {
+            "header": {
+                "similarity": "40.12",
+                "thumbnail": "https://img3.saucenao.com/booru/a/b/example.jpg",
+                "index_id": 9,
+                "index_name": "Index #9: Danbooru - example.jpg"
+            },
+            "data": {
+                "ext_urls": [],
+                "danbooru_id": 1234
+            }
+        }
+    ]
+}"#;
+
+const MERGED_BOORU_RESPONSE: &str = r#"{
+    "header": {
+        "short_limit": "4",
+        "long_limit": "100",
+        "long_remaining": 99,
+        "short_remaining": 3,
+        "status": 0
+    },
+    "results": [
+        {
+            "header": {
+                "similarity": "93.10",
+                "thumbnail": "https://img3.saucenao.com/booru/a/b/example.jpg",
+                "index_id": 9,
+                "index_name": "Index #9: Danbooru - example.jpg"
+            },
+            "data": {
+                "ext_urls": ["https://danbooru.donmai.us/post/show/1234", "https://gelbooru.com/index.php?page=post&s=view&id=5678"],
+                "danbooru_id": 1234,
+                "gelbooru_id": 5678,
+                "creator": "Example Artist",
+                "material": "original",
+                "characters": "",
+                "source": "https://www.pixiv.net/member_illust.php?mode=medium&illust_id=61477678"
+            }
+        }
+    ]
+}"#;
+
+const ANIME_RESPONSE: &str = r#"{
+    "header": {
+        "short_limit": "4",
+        "long_limit": "100",
+        "long_remaining": 99,
+        "short_remaining": 3,
+        "status": 0
+    },
+    "results": [
+        {
+            "header": {
+                "similarity": "95.12",
+                "thumbnail": "https://img3.saucenao.com/anime/example.jpg",
+                "index_id": 21,
+                "index_name": "Index #21: Anime* - example.mkv"
+            },
+            "data": {
+                "ext_urls": ["https://anidb.net/perl-bin/animedb.pl?show=anime&aid=4521"],
+                "source": "Example Anime",
+                "anidb_aid": 4521,
+                "mal_id": "1234",
+                "anilist_id": 5678,
+                "part": "07",
+                "year": "2006-2007",
+                "est_time": "00:12:34 / 00:23:40"
+            }
+        }
+    ]
+}"#;
+
+const MANGA_RESPONSE: &str = r#"{
+    "header": {
+        "short_limit": "4",
+        "long_limit": "100",
+        "long_remaining": 99,
+        "short_remaining": 3,
+        "status": 0
+    },
+    "results": [
+        {
+            "header": {
+                "similarity": "91.30",
+                "thumbnail": "https://img3.saucenao.com/mangadex/example.jpg",
+                "index_id": 37,
+                "index_name": "Index #37: MangaDex - example.jpg"
+            },
+            "data": {
+                "ext_urls": ["https://mangadex.org/chapter/abc-123"],
+                "md_id": "abc-123",
+                "mu_id": 12345,
+                "mal_id": "6789",
+                "source": "Example Manga",
+                "part": " - Chapter 12",
+                "artist": "Example Artist",
+                "author": "Example Author"
+            }
+        },
+        {
+            "header": {
+                "similarity": "88.00",
+                "thumbnail": "https://img3.saucenao.com/ehentai/example.jpg",
+                "index_id": 38,
+                "index_name": "Index #38: H-Misc (E-Hentai) - example.jpg"
+            },
+            "data": {
+                "source": "Example Doujin",
+                "creator": ["Example Circle"],
+                "eng_name": "Example Doujin",
+                "jp_name": "例"
+            }
+        }
+    ]
+}"#;
+
+/// A transport that replies to every request with the same canned response
+#[derive(Debug)]
+struct CannedTransport(String);
+
+impl Transport for CannedTransport {
+    fn send(
+        &self, _request: TransportRequest,
+    ) -> BoxFuture<'_, Result<TransportResponse, BoxError>> {
+        let response = TransportResponse::new(StatusCode::OK, self.0.clone());
+        Box::pin(async move { Ok(response) })
+    }
+}
+
+/// Searches against a canned response, returning the results
+async fn search_ok(body: &str) -> Vec<Sauce> {
+    let handle = HandlerBuilder::default()
+        .api_key("key")
+        .transport(CannedTransport(body.to_string()))
+        .build();
+    handle.get_sauce(FILE, None, None).await.unwrap()
+}
+
+/// Tests the typed data specific to each source
+#[tokio::test]
+async fn test_source_data() {
+    let result = search_ok(RESPONSE).await;
+
+    match result[0].source_data() {
+        SourceData::Pixiv(pixiv) => {
+            assert_eq!(pixiv.pixiv_id, Some(61477678));
+            assert_eq!(pixiv.member_name.as_deref(), Some("Example Artist"));
+            assert_eq!(pixiv.member_id, Some(4754550));
+        }
+        other => panic!("expected Pixiv data, got {:?}", other),
+    }
+    match result[1].source_data() {
+        SourceData::Booru(booru) => assert_eq!(booru.id(), Some(1234)),
+        other => panic!("expected booru data, got {:?}", other),
+    }
+}
+
+/// Tests the typed data of a booru result merged from several boorus
+#[tokio::test]
+async fn test_merged_booru_data() {
+    let result = search_ok(MERGED_BOORU_RESPONSE).await;
+
+    match result[0].source_data() {
+        SourceData::Booru(booru) => {
+            assert_eq!(booru.danbooru_id, Some(1234));
+            assert_eq!(booru.gelbooru_id, Some(5678));
+            assert_eq!(
+                booru.ids(),
+                vec![(Source::Danbooru, 1234), (Source::Gelbooru, 5678)]
+            );
+            assert_eq!(booru.id(), Some(1234));
+            assert_eq!(booru.material.as_deref(), Some("original"));
+        }
+        other => panic!("expected booru data, got {:?}", other),
+    }
+}
+
+/// Tests the typed data of anime results
+#[tokio::test]
+async fn test_anime_data() {
+    let result = search_ok(ANIME_RESPONSE).await;
+
+    let anime = result[0].anime().unwrap();
+    assert_eq!(anime.source.as_deref(), Some("Example Anime"));
+    assert_eq!(anime.anidb_aid, Some(4521));
+    assert_eq!(anime.mal_id, Some(1234));
+    assert_eq!(anime.anilist_id, Some(5678));
+    assert_eq!(anime.episode(), Some(7));
+    assert_eq!(anime.year(), Some(2006));
+    assert_eq!(anime.timestamp(), Some(Duration::from_secs(754)));
+    assert_eq!(anime.total_length(), Some(Duration::from_secs(1420)));
+}
+
+/// Tests that timestamps too large for a Duration are ignored instead of panicking
+#[test]
+fn test_anime_huge_timestamp() {
+    let anime = AnimeData {
+        est_time: Some("99999999999999999999:00:00 / 1e300".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(anime.timestamp(), None);
+    assert_eq!(anime.total_length(), None);
+}
+
+/// Tests the manga view of results from manga and doujin sources
+#[tokio::test]
+async fn test_manga_data() {
+    let result = search_ok(MANGA_RESPONSE).await;
+
+    let manga = result[0].manga().unwrap();
+    assert_eq!(manga.series(), Some("Example Manga"));
+    assert_eq!(manga.chapter(), Some(12));
+    assert_eq!(manga.md_id.as_deref(), Some("abc-123"));
+    assert_eq!(manga.mu_id, Some(12345));
+    assert_eq!(manga.mal_id, Some(6789));
+    assert_eq!(manga.author.as_deref(), Some("Example Author"));
+    assert_eq!(result[0].source_data(), SourceData::MangaDex(manga.clone()));
+
+    let doujin = result[1].manga().unwrap();
+    assert_eq!(doujin.series(), Some("Example Doujin"));
+    assert_eq!(doujin.creator, vec!["Example Circle".to_string()]);
+    assert_eq!(doujin.chapter(), None);
+
+    let body = MANGA_RESPONSE.replace(r#""index_id": 37,"#, r#""index_id": 371,"#);
+    let result = search_ok(&body).await;
+    assert_eq!(result[0].source_data(), SourceData::MangaDex(manga));
+
+    let result = search_ok(ANIME_RESPONSE).await;
+    assert!(result[0].manga().is_none());
+}
+
+/// Tests reading back results that were serialized to JSON
+#[test]
+fn test_sauce_round_trip() {
+    let sauce: Sauce = serde_json::from_value(serde_json::json!({
+        "ext_urls": ["https://www.pixiv.net/member_illust.php?mode=medium&illust_id=61477678"],
+        "title": "Example",
+        "site": "pixiv Images",
+        "index": 5,
+        "index_id": 5,
+        "similarity": 92.51,
+        "thumbnail": "https://img1.saucenao.com/res/pixiv/6147/61477678_p0.jpg",
+        "additional_fields": {"pixiv_id": 61477678, "member_name": "Example Artist", "member_id": 4754550},
+        "source": null,
+        "creator": null,
+        "eng_name": null,
+        "jp_name": null
+    }))
+    .unwrap();
+    let result = vec![sauce];
+
+    let json = result.to_json().unwrap();
+    let read: Vec<Sauce> = serde_json::from_str(&json).unwrap();
+    assert_eq!(read, result);
+
+    let json = result.to_json_pretty().unwrap();
+    let read: Vec<Sauce> = serde_json::from_str(&json).unwrap();
+    assert_eq!(read, result);
+    assert_eq!(read[0].source_data(), result[0].source_data());
+
+    let mut value = serde_json::to_value(&result[0]).unwrap();
+    assert_eq!(value["schema_version"], Sauce::SCHEMA_VERSION);
+
+    // JSON written before the schema was versioned is still read.
+    value.as_object_mut().unwrap().remove("schema_version");
+    let read: Sauce = serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(read, result[0]);
+
+    // JSON from a newer schema is rejected.
+    value["schema_version"] = (Sauce::SCHEMA_VERSION + 1).into();
+    assert!(serde_json::from_value::<Sauce>(value).is_err());
+}
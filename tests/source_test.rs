@@ -0,0 +1,121 @@
+use rustnao::{DbMask, Error, Source, SourceCategory};
+
+/// Tests parsing sources from slugs, names and indices
+#[test]
+fn test_parse_source() {
+    assert_eq!("pixiv".parse::<Source>().unwrap(), Source::Pixiv);
+    assert_eq!("Danbooru".parse::<Source>().unwrap(), Source::Danbooru);
+    assert_eq!("pixiv Images".parse::<Source>().unwrap(), Source::Pixiv);
+    assert_eq!("E-HENTAI".parse::<Source>().unwrap(), Source::EHentai);
+    assert_eq!(" 9 ".parse::<Source>().unwrap(), Source::Danbooru);
+    assert!(matches!(
+        "not a source".parse::<Source>(),
+        Err(Error::InvalidParameters(_))
+    ));
+}
+
+/// Tests that every source round-trips through its slug, name, index and serialization
+#[test]
+fn test_source_round_trip() {
+    for source in Source::all() {
+        assert_eq!(source.slug().parse::<Source>().unwrap(), *source);
+        assert_eq!(source.to_string().parse::<Source>().unwrap(), *source);
+        assert_eq!(Source::from_u32(source.index()), Some(*source));
+
+        let json = serde_json::to_string(source).unwrap();
+        assert_eq!(json, format!("\"{}\"", source.slug()));
+        assert_eq!(serde_json::from_str::<Source>(&json).unwrap(), *source);
+    }
+}
+
+/// Tests reading a list of sources from configuration
+#[test]
+fn test_deserialize_sources() {
+    let sources: Vec<Source> = serde_json::from_str(r#"["pixiv", "Gelbooru", 21]"#).unwrap();
+    assert_eq!(
+        sources,
+        vec![Source::Pixiv, Source::Gelbooru, Source::Anime]
+    );
+    assert!(serde_json::from_str::<Source>(r#""not a source""#).is_err());
+    assert!(serde_json::from_str::<Source>("-1").is_err());
+}
+
+/// Tests that sources are listed once each, in order of index
+#[test]
+fn test_all_sources() {
+    let indices: Vec<u32> = Source::all().iter().map(|x| x.index()).collect();
+    assert!(indices.windows(2).all(|x| x[0] < x[1]));
+    assert_eq!(Source::all().first(), Some(&Source::HMagazines));
+}
+
+/// Tests that unknown indices are kept through parsing and serialization
+#[test]
+fn test_unknown_source() {
+    assert_eq!(Source::from(45), Source::Unknown(45));
+    assert_eq!(Source::from(9), Source::Danbooru);
+    assert_eq!(Source::from(4), Source::DoujinshiDBSamples);
+    assert_eq!(Source::from(371), Source::MangaDexV2);
+    assert_eq!(Source::from(7), Source::Unknown(7));
+    assert_eq!(Source::from_u32(45), None);
+    assert_eq!(u32::from(Source::Unknown(45)), 45);
+    assert_eq!("45".parse::<Source>().unwrap(), Source::Unknown(45));
+    assert_eq!(Source::Unknown(45).to_string(), "Unknown (index 45)");
+
+    let json = serde_json::to_string(&Source::Unknown(45)).unwrap();
+    assert_eq!(json, "45");
+    assert_eq!(
+        serde_json::from_str::<Source>(&json).unwrap(),
+        Source::Unknown(45)
+    );
+}
+
+/// Tests grouping sources by category and content rating
+#[test]
+fn test_source_categories() {
+    assert_eq!(Source::Danbooru.category(), Some(SourceCategory::Booru));
+    assert_eq!(Source::Twitter.category(), Some(SourceCategory::Social));
+    assert_eq!(Source::Unknown(45).category(), None);
+    assert!(Source::EHentai.is_adult());
+    assert!(Source::Kemono.is_adult());
+    assert!(Source::IdolComplex.is_adult());
+    assert!(Source::E621Net.is_adult());
+    assert!(Source::SankakuChannel.is_adult());
+    assert!(!Source::Pixiv.is_adult());
+
+    let boorus = SourceCategory::Booru.sources();
+    assert!(boorus.contains(&Source::Gelbooru));
+    assert!(!boorus.contains(&Source::Pixiv));
+    assert_eq!(DbMask::from(SourceCategory::Booru), DbMask::from(boorus));
+
+    // Every known source is in exactly one category.
+    let categories = [
+        SourceCategory::Video,
+        SourceCategory::Manga,
+        SourceCategory::Booru,
+        SourceCategory::Art,
+        SourceCategory::Social,
+        SourceCategory::Other,
+    ];
+    let total: usize = categories.iter().map(|x| x.sources().len()).sum();
+    assert_eq!(total, Source::all().len());
+
+    let adult = DbMask::adult();
+    assert!(adult.contains(Source::HAnime));
+    assert!(adult.iter().all(|x| x.is_adult()));
+    assert!(adult.complement().iter().all(|x| !x.is_adult()));
+}
+
+/// Tests that sources round-trip through a compact format that is not self-describing
+#[test]
+fn test_source_compact_format() {
+    let sources = vec![Source::Pixiv, Source::Danbooru, Source::Unknown(45)];
+    let bytes = bincode::serialize(&sources).unwrap();
+    assert_eq!(
+        bincode::deserialize::<Vec<Source>>(&bytes).unwrap(),
+        sources
+    );
+    assert_eq!(
+        bincode::serialize(&Source::Pixiv).unwrap(),
+        5u32.to_le_bytes()
+    );
+}
@@ -0,0 +1,917 @@
+use rustnao::reqwest::header::{HeaderValue, CONTENT_TYPE, RETRY_AFTER};
+use rustnao::reqwest::StatusCode;
+use rustnao::{
+    BoxError, BoxFuture, ContentFilter, DbMask, Error, Handler, HandlerBuilder, RetryPolicy,
+    SearchOptions, SimilarityThreshold, Source, SourceCategory, SourceData, Transport,
+    TransportRequest, TransportResponse,
+};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const FILE: &str = "https://i.imgur.com/W42kkKS.jpg";
+const LOCAL_FILE: &str = "./tests/test.jpg";
+
+const RESPONSE: &str = r#"{
+    "header": {
+        "short_limit": "4",
+        "long_limit": "100",
+        "long_remaining": 99,
+        "short_remaining": 3,
+        "status": 0,
+        "message": ""
+    },
+    "results": [
+        {
+            "header": {
+                "similarity": "92.51",
+                "thumbnail": "https://img1.saucenao.com/res/pixiv/6147/61477678_p0.jpg",
+                "index_id": 5,
+                "index_name": "Index #5: Pixiv Images - 61477678_p0.jpg"
+            },
+            "data": {
+                "ext_urls": ["https://www.pixiv.net/member_illust.php?mode=medium&illust_id=61477678"],
+                "title": "Example",
+                "pixiv_id": 61477678,
+                "member_name": "Example Artist",
+                "member_id": 4754550
+            }
+        },
+        {
+            "header": {
+                "similarity": "40.12",
+                "thumbnail": "https://img3.saucenao.com/booru/a/b/example.jpg",
+                "index_id": 9,
+                "index_name": "Index #9: Danbooru - example.jpg"
+            },
+            "data": {
+                "ext_urls": [],
+                "danbooru_id": 1234
+            }
+        }
+    ]
+}"#;
+
+const FULL_HEADER_RESPONSE: &str = r#"{
+    "header": {
+        "user_id": "12345",
+        "account_type": "1",
+        "short_limit": "4",
+        "long_limit": "100",
+        "long_remaining": 99,
+        "short_remaining": 3,
+        "status": 0,
+        "results_requested": "16",
+        "index": {
+            "5": {"status": 0, "parent_id": 5, "id": 5, "results": 16},
+            "9": {"status": -1, "parent_id": 9, "id": 9}
+        },
+        "search_depth": "128",
+        "minimum_similarity": 54.79,
+        "query_image_display": "/userdata/example.png",
+        "query_image": "example.png",
+        "results_returned": 1
+    },
+    "results": []
+}"#;
+
+const ODD_TYPES_RESPONSE: &str = r#"{
+    "header": {
+        "short_limit": 4,
+        "long_limit": "100",
+        "long_remaining": "99",
+        "short_remaining": 3,
+        "status": "0",
+        "minimum_similarity": "54.79"
+    },
+    "results": [
+        {
+            "header": {
+                "similarity": 88.5,
+                "thumbnail": "https://img3.saucenao.com/booru/a/b/example.jpg",
+                "index_id": "9",
+                "index_name": "Index #9: Danbooru - example.jpg"
+            },
+            "data": {
+                "ext_urls": null,
+                "creator": "Example Artist",
+                "title": 1234,
+                "danbooru_id": "1234"
+            }
+        }
+    ]
+}"#;
+
+const MALFORMED_RESPONSE: &str = r#"{
+    "header": {
+        "short_limit": "",
+        "long_limit": "",
+        "long_remaining": 99,
+        "short_remaining": 3,
+        "status": 0,
+        "message": ""
+    },
+    "results": [
+        {
+            "header": {
+                "similarity": "not a number",
+                "thumbnail": "https://img3.saucenao.com/example.jpg",
+                "index_id": 5,
+                "index_name": "Index #5: Pixiv Images - example.jpg"
+            },
+            "data": {}
+        },
+        {
+            "header": {
+                "similarity": "91.00",
+                "index_id": 5
+            },
+            "data": "not an object"
+        },
+        {
+            "header": {
+                "similarity": "70.00",
+                "thumbnail": "https://img3.saucenao.com/example.jpg",
+                "index_name": "Index #5: Pixiv Images - example.jpg"
+            },
+            "data": {}
+        },
+        {
+            "header": {
+                "similarity": "92.51",
+                "thumbnail": "https://img1.saucenao.com/res/pixiv/6147/61477678_p0.jpg",
+                "index_id": 5,
+                "index_name": "Index #5: Pixiv Images - 61477678_p0.jpg"
+            },
+            "data": {
+                "ext_urls": ["https://www.pixiv.net/member_illust.php?mode=medium&illust_id=61477678"],
+                "pixiv_id": 61477678
+            }
+        }
+    ]
+}"#;
+
+const EXHAUSTED_RESPONSE: &str = r#"{
+    "header": {
+        "short_limit": "4",
+        "long_limit": "100",
+        "long_remaining": 50,
+        "short_remaining": 0,
+        "status": 0
+    },
+    "results": []
+}"#;
+
+const DAILY_EXHAUSTED_RESPONSE: &str = r#"{
+    "header": {
+        "short_limit": "4",
+        "long_limit": "100",
+        "long_remaining": 0,
+        "short_remaining": 3,
+        "status": 0
+    },
+    "results": []
+}"#;
+
+const SERVER_ERROR_RESPONSE: &str = r#"{
+    "header": {
+        "status": 1,
+        "message": "The search engine is currently overloaded."
+    }
+}"#;
+
+const BAD_IMAGE_RESPONSE: &str = r#"{
+    "header": {
+        "status": -3,
+        "message": "The image could not be processed."
+    }
+}"#;
+
+const SHORT_LIMIT_RESPONSE: &str = r#"{
+    "header": {
+        "status": -2,
+        "message": "Search Rate Too High. Your IP has exceeded the basic account type's rate limit of 4 searches every 30 seconds."
+    }
+}"#;
+
+const LONG_LIMIT_RESPONSE: &str = r#"{
+    "header": {
+        "status": -2,
+        "message": "Daily Search Limit Exceeded. Your IP has exceeded the basic account type's daily limit of 100 searches."
+    }
+}"#;
+
+const HTML_RESPONSE: &str = "<html><body>Service Unavailable</body></html>";
+
+/// A transport that records every request and replies with canned responses, repeating the last
+#[derive(Debug, Clone)]
+struct FakeTransport {
+    responses: Arc<Mutex<Vec<TransportResponse>>>,
+    requests: Arc<Mutex<Vec<TransportRequest>>>,
+}
+
+impl FakeTransport {
+    fn new(status: StatusCode, body: &'static str) -> FakeTransport {
+        FakeTransport::sequence(vec![(status, body)])
+    }
+
+    fn sequence(responses: Vec<(StatusCode, &'static str)>) -> FakeTransport {
+        let responses = responses
+            .into_iter()
+            .map(|(status, body)| TransportResponse::new(status, body))
+            .collect();
+        FakeTransport::responses(responses)
+    }
+
+    fn responses(responses: Vec<TransportResponse>) -> FakeTransport {
+        FakeTransport {
+            responses: Arc::new(Mutex::new(responses)),
+            requests: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn requests(&self) -> Vec<TransportRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Transport for FakeTransport {
+    fn send(
+        &self, request: TransportRequest,
+    ) -> BoxFuture<'_, Result<TransportResponse, BoxError>> {
+        self.requests.lock().unwrap().push(request);
+        let mut responses = self.responses.lock().unwrap();
+        let response = if responses.len() > 1 {
+            responses.remove(0)
+        } else {
+            responses[0].clone()
+        };
+        Box::pin(async move { Ok(response) })
+    }
+}
+
+/// Creates a handler backed by a fake transport for testing purposes
+fn create_handler(transport: &FakeTransport) -> Handler {
+    HandlerBuilder::default()
+        .api_key("key")
+        .transport(transport.clone())
+        .build()
+}
+
+/// Tests that results are parsed from the transport response
+#[tokio::test]
+async fn test_results_from_transport() {
+    let transport = FakeTransport::new(StatusCode::OK, RESPONSE);
+    let handle = create_handler(&transport);
+    let result = handle.get_sauce(FILE, None, None).await.unwrap();
+    assert_eq!(result.len(), 2);
+    assert_eq!(result[0].index, Source::Pixiv.index());
+    assert_eq!(result[0].title.as_deref(), Some("Example"));
+    assert_eq!(handle.get_current_short_limit(), 3);
+    assert_eq!(handle.get_current_long_limit(), 99);
+}
+
+/// Tests the metadata kept from the response header
+#[tokio::test]
+async fn test_search_response_meta() {
+    let transport = FakeTransport::new(StatusCode::OK, FULL_HEADER_RESPONSE);
+    let handle = create_handler(&transport);
+    let response = handle.get_sauce_response(FILE, None, None).await.unwrap();
+    let meta = response.meta;
+
+    assert!(response.results.is_empty());
+    assert_eq!(meta.user_id, Some(12345));
+    assert_eq!(meta.account_type, Some(1));
+    assert!(!meta.is_premium());
+    assert_eq!((meta.short_limit, meta.long_limit), (Some(4), Some(100)));
+    assert_eq!(
+        (meta.short_remaining, meta.long_remaining),
+        (Some(3), Some(99))
+    );
+    assert_eq!(meta.results_requested, Some(16));
+    assert_eq!(meta.results_returned, Some(1));
+    assert_eq!(meta.search_depth, Some(128));
+    assert_eq!(meta.minimum_similarity, Some(54.79));
+    assert_eq!(meta.query_image.as_deref(), Some("example.png"));
+    assert_eq!(
+        meta.query_image_display.as_deref(),
+        Some("/userdata/example.png")
+    );
+    assert_eq!(meta.message, None);
+    assert_eq!(meta.indexes.len(), 2);
+    assert_eq!(meta.indexes[&5].results, Some(16));
+    assert_eq!(meta.failed_indexes(), vec![9]);
+
+    let transport = FakeTransport::new(StatusCode::OK, RESPONSE);
+    let handle = create_handler(&transport);
+    let response = handle.get_sauce_response(FILE, None, None).await.unwrap();
+    assert_eq!(response.results.len(), 2);
+    assert_eq!(response.meta.minimum_similarity, None);
+    assert!(response.meta.indexes.is_empty());
+}
+
+/// Tests filtering by the minimum similarity suggested by SauceNAO
+#[tokio::test]
+async fn test_similarity_threshold() {
+    let body = RESPONSE.replace(
+        r#""status": 0,"#,
+        r#""status": 0, "minimum_similarity": 35.5,"#,
+    );
+    let transport = FakeTransport::responses(vec![TransportResponse::new(StatusCode::OK, body)]);
+    let handle = create_handler(&transport);
+
+    let result = handle.get_sauce(FILE, None, Some(50.0)).await.unwrap();
+    assert_eq!(result.len(), 1);
+
+    // The suggestion replaces the Handler's minimum similarity.
+    handle.set_similarity_threshold(SimilarityThreshold::ServerSuggested);
+    handle.set_min_similarity(95);
+    let result = handle.get_sauce(FILE, None, None).await.unwrap();
+    assert_eq!(result.len(), 2);
+
+    // A minimum similarity given to the search is kept, with the higher of it and the suggestion used.
+    let result = handle.get_sauce(FILE, None, Some(50.0)).await.unwrap();
+    assert_eq!(result.len(), 1);
+    let result = handle.get_sauce(FILE, None, Some(95.0)).await.unwrap();
+    assert!(result.is_empty());
+    let result = handle.get_sauce(FILE, None, Some(20.0)).await.unwrap();
+    assert_eq!(result.len(), 2);
+    let mut options = SearchOptions::default();
+    options.min_similarity(50);
+    let response = handle.search(FILE, &options).await.unwrap();
+    assert_eq!(response.results.len(), 1);
+
+    handle.set_similarity_threshold(SimilarityThreshold::Max);
+    let result = handle.get_sauce(FILE, None, Some(20.0)).await.unwrap();
+    assert_eq!(result.len(), 2);
+    let result = handle.get_sauce(FILE, None, Some(50.0)).await.unwrap();
+    assert_eq!(result.len(), 1);
+
+    // Without a suggestion, the set minimum similarity is used.
+    let transport = FakeTransport::new(StatusCode::OK, RESPONSE);
+    let handle = HandlerBuilder::default()
+        .transport(transport.clone())
+        .min_similarity(50)
+        .similarity_threshold(SimilarityThreshold::ServerSuggested)
+        .build();
+    let result = handle.get_sauce(FILE, None, None).await.unwrap();
+    assert_eq!(result.len(), 1);
+}
+
+/// Tests reading values that are sent as the wrong type or are missing
+#[tokio::test]
+async fn test_odd_types() {
+    let transport = FakeTransport::new(StatusCode::OK, ODD_TYPES_RESPONSE);
+    let handle = create_handler(&transport);
+    let response = handle.get_sauce_response(FILE, None, None).await.unwrap();
+
+    assert!(response.warnings.is_empty());
+    assert_eq!(response.meta.short_limit, Some(4));
+    assert_eq!(response.meta.long_remaining, Some(99));
+    assert_eq!(response.meta.minimum_similarity, Some(54.79));
+    assert_eq!(handle.get_current_long_limit(), 99);
+
+    let sauce = &response.results[0];
+    assert_eq!(sauce.similarity, 88.5);
+    assert_eq!(sauce.index_id, 9);
+    assert!(sauce.ext_urls.is_empty());
+    assert_eq!(sauce.title.as_deref(), Some("1234"));
+    assert_eq!(sauce.creator, Some(vec!["Example Artist".to_string()]));
+    match sauce.source_data() {
+        SourceData::Booru(booru) => {
+            assert_eq!(booru.id(), Some(1234));
+            assert_eq!(booru.creator, vec!["Example Artist".to_string()]);
+        }
+        other => panic!("expected booru data, got {:?}", other),
+    }
+}
+
+/// Tests that malformed results and limits are skipped with warnings instead of failing the search
+#[tokio::test]
+async fn test_malformed_results() {
+    let transport = FakeTransport::new(StatusCode::OK, MALFORMED_RESPONSE);
+    let handle = create_handler(&transport);
+    let response = handle.get_sauce_response(FILE, None, None).await.unwrap();
+
+    assert_eq!(response.results.len(), 1);
+    assert_eq!(response.results[0].similarity, 92.51);
+
+    let warned: Vec<Option<usize>> = response.warnings.iter().map(|x| x.result).collect();
+    assert_eq!(warned, vec![None, None, Some(0), Some(1), Some(2)]);
+    assert_eq!(
+        response.warnings[2].to_string(),
+        "result 0: similarity is missing or not a number"
+    );
+    assert_eq!(
+        response.warnings[4].to_string(),
+        "result 2: index_id is missing or not a number"
+    );
+
+    // The limits that were sent are still recorded.
+    assert_eq!(handle.get_current_short_limit(), 3);
+    assert_eq!(handle.get_current_long_limit(), 99);
+
+    // Searches returning only the results also skip the malformed ones.
+    let result = handle.get_sauce(FILE, None, None).await.unwrap();
+    assert_eq!(result.len(), 1);
+}
+
+/// Tests filtering by minimum similarity and empty URLs
+#[tokio::test]
+async fn test_filters_from_transport() {
+    let transport = FakeTransport::new(StatusCode::OK, RESPONSE);
+    let handle = create_handler(&transport);
+    let result = handle.get_sauce(FILE, None, Some(50.0)).await.unwrap();
+    assert_eq!(result.len(), 1);
+
+    handle.set_empty_filter(true);
+    let result = handle.get_sauce(FILE, None, None).await.unwrap();
+    assert!(result.iter().all(|sauce| !sauce.has_empty_url()));
+}
+
+/// Tests the request sent for a URL search
+#[tokio::test]
+async fn test_url_request() {
+    let transport = FakeTransport::new(StatusCode::OK, RESPONSE);
+    let handle = HandlerBuilder::default()
+        .api_key("key")
+        .db_mask(vec![Source::Pixiv])
+        .transport(transport.clone())
+        .build();
+    handle.get_sauce(FILE, Some(5), None).await.unwrap();
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].upload.is_none());
+    let query: Vec<(String, String)> = requests[0].url.query_pairs().into_owned().collect();
+    assert!(query.contains(&("api_key".to_string(), "key".to_string())));
+    assert!(query.contains(&("dbmask".to_string(), "32".to_string())));
+    assert!(query.contains(&("numres".to_string(), "5".to_string())));
+    assert!(query.contains(&("url".to_string(), FILE.to_string())));
+}
+
+/// Tests that requests are sent to the base URL set on the builder
+#[tokio::test]
+async fn test_base_url_request() {
+    let transport = FakeTransport::new(StatusCode::OK, RESPONSE);
+    let handle = HandlerBuilder::default()
+        .api_key("key")
+        .base_url("http://localhost:8080/search.php")
+        .transport(transport.clone())
+        .build();
+    handle.get_sauce(FILE, None, None).await.unwrap();
+
+    let url = &transport.requests()[0].url;
+    assert_eq!(url.scheme(), "http");
+    assert_eq!(url.host_str(), Some("localhost"));
+    assert_eq!(url.port(), Some(8080));
+    assert_eq!(url.path(), "/search.php");
+    let query: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+    assert!(query.contains(&("api_key".to_string(), "key".to_string())));
+}
+
+/// Tests that unknown indices are kept in masks and results
+#[tokio::test]
+async fn test_unknown_source() {
+    let body = RESPONSE.replace(r#""index_id": 9,"#, r#""index_id": 45,"#);
+    let transport = FakeTransport::responses(vec![TransportResponse::new(StatusCode::OK, body)]);
+    let handle = HandlerBuilder::default()
+        .api_key("key")
+        .db_mask(vec![Source::Unknown(45), Source::Pixiv])
+        .transport(transport.clone())
+        .build();
+    let result = handle.get_sauce(FILE, None, None).await.unwrap();
+
+    let query: Vec<(String, String)> = transport.requests()[0]
+        .url
+        .query_pairs()
+        .into_owned()
+        .collect();
+    let mask = ((1u64 << 45) | (1 << 5)).to_string();
+    assert!(query.contains(&("dbmask".to_string(), mask)));
+
+    // Indices above 63 are encoded without overflowing.
+    let handle = HandlerBuilder::default()
+        .db_mask_i(vec![Source::Unknown(64), Source::HMagazines])
+        .transport(transport.clone())
+        .build();
+    handle.get_sauce(FILE, None, None).await.unwrap();
+    let query: Vec<(String, String)> = transport.requests()[1]
+        .url
+        .query_pairs()
+        .into_owned()
+        .collect();
+    let mask = "18446744073709551617".to_string();
+    assert!(query.contains(&("dbmaski".to_string(), mask)));
+
+    assert_eq!(Source::from(result[1].index_id), Source::Unknown(45));
+    assert_eq!(result[1].site, "Index #9: Danbooru - example.jpg");
+}
+
+/// Tests the content filter sent with requests
+#[tokio::test]
+async fn test_content_filter_request() {
+    let hide = |transport: &FakeTransport| {
+        let requests = transport.requests();
+        requests
+            .last()
+            .unwrap()
+            .url
+            .query_pairs()
+            .find(|(key, _)| key == "hide")
+            .map(|(_, value)| value.into_owned())
+    };
+
+    let transport = FakeTransport::new(StatusCode::OK, RESPONSE);
+    let handle = create_handler(&transport);
+    handle.get_sauce(FILE, None, None).await.unwrap();
+    assert_eq!(hide(&transport), None);
+
+    let handle = HandlerBuilder::default()
+        .content_filter(ContentFilter::HideSuspected)
+        .transport(transport.clone())
+        .build();
+    handle.get_sauce(FILE, None, None).await.unwrap();
+    assert_eq!(hide(&transport).as_deref(), Some("2"));
+
+    handle.set_content_filter(ContentFilter::SafeOnly);
+    handle.get_sauce(FILE, None, None).await.unwrap();
+    assert_eq!(hide(&transport).as_deref(), Some("3"));
+}
+
+/// Tests that search options override the Handler defaults for a single search
+#[tokio::test]
+async fn test_search_options() {
+    let transport = FakeTransport::new(StatusCode::OK, RESPONSE);
+    let handle = HandlerBuilder::default()
+        .api_key("key")
+        .db_mask(vec![Source::Pixiv])
+        .num_results(10)
+        .min_similarity(50)
+        .transport(transport.clone())
+        .build();
+
+    let response = handle
+        .search(
+            FILE,
+            SearchOptions::default()
+                .db(999)
+                .db_mask(SourceCategory::Booru)
+                .db_mask_i(vec![Source::Danbooru])
+                .testmode(true)
+                .num_results(3)
+                .min_similarity(10)
+                .empty_filter_enabled(true)
+                .content_filter(ContentFilter::HideExpected),
+        )
+        .await
+        .unwrap();
+    // The danbooru result passes the lower minimum similarity, but not the empty filter.
+    assert_eq!(response.results.len(), 1);
+
+    let query = |i: usize| -> Vec<(String, String)> {
+        transport.requests()[i]
+            .url
+            .query_pairs()
+            .into_owned()
+            .collect()
+    };
+    let pair = |key: &str, value: String| (key.to_string(), value);
+    let overridden = query(0);
+    assert!(overridden.contains(&pair("db", "999".to_string())));
+    assert!(overridden.contains(&pair(
+        "dbmask",
+        DbMask::from(SourceCategory::Booru).to_string()
+    )));
+    assert!(overridden.contains(&pair("dbmaski", "512".to_string())));
+    assert!(overridden.contains(&pair("testmode", "1".to_string())));
+    assert!(overridden.contains(&pair("numres", "3".to_string())));
+    assert!(overridden.contains(&pair("hide", "1".to_string())));
+
+    // The Handler defaults are unchanged for later searches.
+    let result = handle
+        .search(FILE, &SearchOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(result.results.len(), 1);
+    let default = query(1);
+    assert!(default.contains(&pair("dbmask", "32".to_string())));
+    assert!(default.contains(&pair("testmode", "0".to_string())));
+    assert!(default.contains(&pair("numres", "10".to_string())));
+    assert!(!default.iter().any(|(key, _)| key == "db" || key == "hide"));
+
+    let result = handle.get_sauce(FILE, None, Some(10.0)).await.unwrap();
+    assert_eq!(result.len(), 2);
+
+    let invalid = handle
+        .search(FILE, SearchOptions::default().num_results(0))
+        .await;
+    assert!(matches!(invalid, Err(Error::InvalidParameters(_))));
+}
+
+/// Tests the request sent for a local file search
+#[tokio::test]
+async fn test_local_request() {
+    let transport = FakeTransport::new(StatusCode::OK, RESPONSE);
+    let handle = create_handler(&transport);
+    handle.get_sauce(LOCAL_FILE, None, None).await.unwrap();
+
+    let requests = transport.requests();
+    let upload = requests[0].upload.as_ref().unwrap();
+    assert_eq!(upload.file_name, "test.jpg");
+    assert_eq!(upload.data, std::fs::read(LOCAL_FILE).unwrap());
+    assert!(!requests[0].url.query_pairs().any(|(key, _)| key == "url"));
+}
+
+/// Tests the request sent for an in-memory image search
+#[tokio::test]
+async fn test_bytes_request() {
+    let transport = FakeTransport::new(StatusCode::OK, RESPONSE);
+    let handle = create_handler(&transport);
+    handle
+        .get_sauce_bytes(&[1, 2, 3], "memory.png", None, None)
+        .await
+        .unwrap();
+
+    let requests = transport.requests();
+    let upload = requests[0].upload.as_ref().unwrap();
+    assert_eq!(upload.file_name, "memory.png");
+    assert_eq!(upload.data, vec![1, 2, 3]);
+}
+
+/// Tests the request sent for an image read from an async reader
+#[tokio::test]
+async fn test_reader_request() {
+    let transport = FakeTransport::new(StatusCode::OK, RESPONSE);
+    let handle = create_handler(&transport);
+    let data: &[u8] = &[4, 5, 6];
+    handle
+        .get_sauce_reader(data, "reader.png", None, None)
+        .await
+        .unwrap();
+
+    let requests = transport.requests();
+    let upload = requests[0].upload.as_ref().unwrap();
+    assert_eq!(upload.file_name, "reader.png");
+    assert_eq!(upload.data, vec![4, 5, 6]);
+}
+
+/// Tests that file URLs are uploaded as local files
+#[tokio::test]
+async fn test_file_url_request() {
+    let transport = FakeTransport::new(StatusCode::OK, RESPONSE);
+    let handle = create_handler(&transport);
+    let path = std::fs::canonicalize(LOCAL_FILE).unwrap();
+    let url = rustnao::reqwest::Url::from_file_path(path).unwrap();
+    handle.get_sauce(url, None, None).await.unwrap();
+
+    let requests = transport.requests();
+    let upload = requests[0].upload.as_ref().unwrap();
+    assert_eq!(upload.file_name, "test.jpg");
+}
+
+/// Tests that unsupported schemes and paths that look like URLs are never sent
+#[tokio::test]
+async fn test_input_classification() {
+    let transport = FakeTransport::new(StatusCode::OK, RESPONSE);
+    let handle = create_handler(&transport);
+
+    let result = handle
+        .get_sauce("ftp://example.com/image.png", None, None)
+        .await;
+    assert!(matches!(result, Err(Error::InvalidParameters(_))));
+
+    let result = handle.get_sauce("http_cache.png", None, None).await;
+    assert!(matches!(result, Err(Error::InvalidFile(_))));
+
+    assert!(transport.requests().is_empty());
+}
+
+/// Tests that the rate limiter waits for the short limit to reset
+#[tokio::test(start_paused = true)]
+async fn test_rate_limit_short() {
+    let transport = FakeTransport::new(StatusCode::OK, EXHAUSTED_RESPONSE);
+    let handle = HandlerBuilder::default()
+        .rate_limit(true)
+        .transport(transport.clone())
+        .build();
+
+    let start = tokio::time::Instant::now();
+    handle.get_sauce(FILE, None, None).await.unwrap();
+    assert!(start.elapsed() < Duration::from_secs(1));
+    handle.get_sauce(FILE, None, None).await.unwrap();
+    assert!(start.elapsed() >= Duration::from_secs(30));
+    assert_eq!(transport.requests().len(), 2);
+}
+
+/// Tests that the rate limiter refuses searches once the long limit is used up
+#[tokio::test]
+async fn test_rate_limit_long() {
+    let transport = FakeTransport::new(StatusCode::OK, DAILY_EXHAUSTED_RESPONSE);
+    let handle = HandlerBuilder::default()
+        .rate_limit(true)
+        .transport(transport.clone())
+        .build();
+
+    handle.get_sauce(FILE, None, None).await.unwrap();
+    let result = handle.get_sauce(FILE, None, None).await;
+    assert!(matches!(result, Err(Error::LongLimitReached(_))));
+    assert_eq!(transport.requests().len(), 1);
+}
+
+/// Tests that transient failures are retried with the retry policy
+#[tokio::test(start_paused = true)]
+async fn test_retry_server_error() {
+    let transport = FakeTransport::sequence(vec![
+        (StatusCode::OK, SERVER_ERROR_RESPONSE),
+        (StatusCode::OK, RESPONSE),
+    ]);
+    let handle = HandlerBuilder::default()
+        .retry_policy(RetryPolicy {
+            jitter: false,
+            ..RetryPolicy::default()
+        })
+        .transport(transport.clone())
+        .build();
+
+    let start = tokio::time::Instant::now();
+    let result = handle.get_sauce(FILE, None, None).await.unwrap();
+    assert_eq!(result.len(), 2);
+    assert_eq!(transport.requests().len(), 2);
+    assert!(start.elapsed() >= Duration::from_secs(1));
+}
+
+/// Tests that retrying an upload shares its buffer instead of copying it
+#[tokio::test(start_paused = true)]
+async fn test_retry_upload() {
+    let transport = FakeTransport::sequence(vec![
+        (StatusCode::OK, SERVER_ERROR_RESPONSE),
+        (StatusCode::OK, RESPONSE),
+    ]);
+    let handle = HandlerBuilder::default()
+        .retry_policy(RetryPolicy::default())
+        .transport(transport.clone())
+        .build();
+    handle
+        .get_sauce_bytes(&[1, 2, 3], "memory.png", None, None)
+        .await
+        .unwrap();
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 2);
+    let first = &requests[0].upload.as_ref().unwrap().data;
+    let second = &requests[1].upload.as_ref().unwrap().data;
+    assert_eq!(second, &vec![1, 2, 3]);
+    assert_eq!(first.as_ptr(), second.as_ptr());
+}
+
+/// Tests that failures are not retried without a retry policy, or when they are not transient
+#[tokio::test(start_paused = true)]
+async fn test_no_retry() {
+    let transport = FakeTransport::new(StatusCode::OK, SERVER_ERROR_RESPONSE);
+    let handle = create_handler(&transport);
+    let result = handle.get_sauce(FILE, None, None).await;
+    assert!(matches!(result, Err(Error::ServerError { code: 1, .. })));
+    assert_eq!(transport.requests().len(), 1);
+
+    let transport = FakeTransport::new(StatusCode::OK, BAD_IMAGE_RESPONSE);
+    let handle = HandlerBuilder::default()
+        .retry_policy(RetryPolicy::default())
+        .transport(transport.clone())
+        .build();
+    let result = handle.get_sauce(FILE, None, None).await;
+    assert!(result.is_err());
+    assert_eq!(transport.requests().len(), 1);
+}
+
+/// Searches against a canned response, returning the error
+async fn search_error(body: &'static str) -> Error {
+    search_error_with(StatusCode::OK, body).await
+}
+
+/// Searches against a canned response with a status code, returning the error
+async fn search_error_with(status: StatusCode, body: &'static str) -> Error {
+    let transport = FakeTransport::new(status, body);
+    let handle = create_handler(&transport);
+    handle.get_sauce(FILE, None, None).await.unwrap_err()
+}
+
+/// Tests that SauceNAO status codes are mapped to structured errors
+#[tokio::test]
+async fn test_status_errors() {
+    let err = search_error(SERVER_ERROR_RESPONSE).await;
+    assert!(matches!(err, Error::ServerError { code: 1, .. }));
+    assert!(err.is_retryable());
+
+    let err = search_error(BAD_IMAGE_RESPONSE).await;
+    assert!(matches!(err, Error::InvalidImage { code: -3, .. }));
+    assert!(!err.is_retryable());
+
+    let err = search_error(SHORT_LIMIT_RESPONSE).await;
+    assert!(matches!(err, Error::ShortLimitReached(_)));
+    assert!(err.is_retryable());
+
+    let err = search_error(LONG_LIMIT_RESPONSE).await;
+    assert!(matches!(err, Error::LongLimitReached(_)));
+    assert!(!err.is_retryable());
+}
+
+/// Searches against a response with a status code and message, returning the error
+async fn status_error(code: i32, message: &str) -> Error {
+    let body = serde_json::json!({"header": {"status": code, "message": message}});
+    let transport = FakeTransport::responses(vec![TransportResponse::new(
+        StatusCode::OK,
+        body.to_string(),
+    )]);
+    let handle = create_handler(&transport);
+    handle.get_sauce(FILE, None, None).await.unwrap_err()
+}
+
+/// Tests that messages which only resemble SauceNAO's phrases are not taken as limits or image errors
+#[tokio::test]
+async fn test_status_near_misses() {
+    let err = status_error(-1, "Failed to generate an accurate result.").await;
+    assert!(matches!(err, Error::InvalidCode { code: -1, .. }));
+    assert!(!err.is_retryable());
+
+    let err = status_error(-5, "Could not read the image header from the request.").await;
+    assert!(matches!(err, Error::InvalidCode { code: -5, .. }));
+
+    let err = status_error(3, "Search rate too high on a separate index.").await;
+    assert!(matches!(err, Error::ServerError { code: 3, .. }));
+
+    let err = status_error(-1, "Search Rate Too High.").await;
+    assert!(matches!(err, Error::ShortLimitReached(_)));
+
+    let err = status_error(-1, "The image could not be processed.").await;
+    assert!(matches!(err, Error::InvalidImage { code: -1, .. }));
+}
+
+/// A transport whose requests always time out
+#[derive(Debug)]
+struct TimeoutTransport;
+
+impl Transport for TimeoutTransport {
+    fn send(
+        &self, _request: TransportRequest,
+    ) -> BoxFuture<'_, Result<TransportResponse, BoxError>> {
+        Box::pin(async {
+            let err = std::io::Error::new(std::io::ErrorKind::TimedOut, "timed out");
+            Err(err.into())
+        })
+    }
+}
+
+/// Tests that the underlying error of a failed request is kept
+#[tokio::test]
+async fn test_error_source() {
+    let handle = HandlerBuilder::default()
+        .transport(TimeoutTransport)
+        .build();
+    let err = handle.get_sauce(FILE, None, None).await.unwrap_err();
+    assert!(matches!(err, Error::InvalidRequest(_)));
+    assert!(err.is_timeout());
+    assert!(!err.is_connect());
+    assert_eq!(err.io_kind(), Some(std::io::ErrorKind::TimedOut));
+    assert!(std::error::Error::source(&err).is_some());
+}
+
+/// Tests that HTTP errors and responses that are not JSON are reported with the response
+#[tokio::test]
+async fn test_http_errors() {
+    let err = search_error_with(StatusCode::SERVICE_UNAVAILABLE, HTML_RESPONSE).await;
+    assert!(matches!(err, Error::HttpServerError { ref body, .. } if body == HTML_RESPONSE));
+    assert_eq!(err.status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+    assert!(err.is_retryable());
+
+    let err = search_error_with(StatusCode::FORBIDDEN, HTML_RESPONSE).await;
+    assert!(matches!(err, Error::Forbidden { .. }));
+    assert!(!err.is_retryable());
+
+    let err = search_error_with(StatusCode::TOO_MANY_REQUESTS, SHORT_LIMIT_RESPONSE).await;
+    assert!(matches!(err, Error::ShortLimitReached(_)));
+
+    let mut response = TransportResponse::new(StatusCode::TOO_MANY_REQUESTS, HTML_RESPONSE);
+    response
+        .headers
+        .insert(RETRY_AFTER, HeaderValue::from_static("12"));
+    let transport = FakeTransport::responses(vec![response]);
+    let err = create_handler(&transport)
+        .get_sauce(FILE, None, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::TooManyRequests { .. }));
+    assert_eq!(err.retry_after(), Some(Duration::from_secs(12)));
+
+    let mut response = TransportResponse::new(StatusCode::OK, HTML_RESPONSE);
+    response
+        .headers
+        .insert(CONTENT_TYPE, HeaderValue::from_static("text/html"));
+    let transport = FakeTransport::responses(vec![response]);
+    let err = create_handler(&transport)
+        .get_sauce(FILE, None, None)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, Error::UnexpectedResponse { ref content_type, .. } if content_type.as_deref() == Some("text/html"))
+    );
+}